@@ -0,0 +1,430 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_quote, Data, DataEnum, DataStruct, DeriveInput, Error, ExprPath, Fields,
+    FieldsNamed, Generics, LitByteStr, LitStr,
+};
+
+use crate::util;
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> Result<Self, Error> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            _ => {
+                return Err(Error::new(
+                    lit.span(),
+                    r#"expected one of "lowercase", "UPPERCASE", "PascalCase", "camelCase", "snake_case", "SCREAMING_SNAKE_CASE", or "kebab-case""#,
+                ))
+            }
+        })
+    }
+
+    fn apply(self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            Self::Lower => words.concat().to_lowercase(),
+            Self::Upper => words.concat().to_uppercase(),
+            Self::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_lowercase()
+                    } else {
+                        capitalize(w)
+                    }
+                })
+                .collect(),
+            Self::Snake => words.join("_").to_lowercase(),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+            Self::Kebab => words.join("-").to_lowercase(),
+        }
+    }
+}
+
+/// Split an identifier in to words, on `_` and case changes, e.g.
+/// `HTTPServer_error` is split in to `HTTP`, `Server`, and `error`.
+fn split_words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).map(|n| n.is_lowercase()).unwrap_or(false);
+            if !prev.is_uppercase() || next_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    string_keys: bool,
+    rename_all: Option<RenameRule>,
+    accept_string: bool,
+}
+
+impl ContainerAttrs {
+    fn parse(input: &DeriveInput) -> Result<Self, Error> {
+        let mut attrs = Self::default();
+        let attr = match util::get_magnus_attrubute(&input.attrs)? {
+            Some(v) => v,
+            None => return Ok(attrs),
+        };
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("string_keys") {
+                attrs.string_keys = true;
+                Ok(())
+            } else if meta.path.is_ident("rename_all") {
+                attrs.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+                Ok(())
+            } else if meta.path.is_ident("accept_string") {
+                attrs.accept_string = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        Ok(attrs)
+    }
+
+    fn name(&self, ident: &syn::Ident, rename: Option<String>) -> String {
+        rename.unwrap_or_else(|| match self.rename_all {
+            Some(rule) => rule.apply(&ident.unraw().to_string()),
+            None => ident.unraw().to_string(),
+        })
+    }
+}
+
+enum FieldDefault {
+    None,
+    Default,
+    Path(ExprPath),
+}
+
+struct FieldAttrs {
+    rename: Option<String>,
+    default: FieldDefault,
+    skip: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &syn::Field) -> Result<Self, Error> {
+        let mut attrs = Self {
+            rename: None,
+            default: FieldDefault::None,
+            skip: false,
+        };
+        let attr = match util::get_magnus_attrubute(&field.attrs)? {
+            Some(v) => v,
+            None => return Ok(attrs),
+        };
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                attrs.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                attrs.default = if meta.input.peek(syn::Token![=]) {
+                    FieldDefault::Path(meta.value()?.parse::<LitStr>()?.parse()?)
+                } else {
+                    FieldDefault::Default
+                };
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        Ok(attrs)
+    }
+}
+
+fn parse_variant_rename(variant: &syn::Variant) -> Result<Option<String>, Error> {
+    let mut rename = None;
+    if let Some(attr) = util::get_magnus_attrubute(&variant.attrs)? {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+    Ok(rename)
+}
+
+/// Returns the variant idents of a fieldless enum, paired with their Ruby
+/// Symbol names.
+fn unit_variants(
+    container: &ContainerAttrs,
+    data: &DataEnum,
+) -> Result<Vec<(syn::Ident, String)>, Error> {
+    data.variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(Error::new_spanned(
+                    variant,
+                    "can only be derived for enums with unit variants",
+                ));
+            }
+            let rename = parse_variant_rename(variant)?;
+            Ok((
+                variant.ident.clone(),
+                container.name(&variant.ident, rename),
+            ))
+        })
+        .collect()
+}
+
+fn unsupported(input: &DeriveInput) -> Error {
+    Error::new_spanned(
+        input,
+        "can only be derived for structs with named fields or enums with unit variants",
+    )
+}
+
+fn add_bounds(generics: &Generics, bound: syn::Path) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+pub fn expand_derive_try_convert(input: DeriveInput) -> Result<TokenStream, Error> {
+    let container = ContainerAttrs::parse(&input)?;
+    let body = match input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => try_convert_struct(&container, fields)?,
+        Data::Enum(ref data) => try_convert_enum(&container, data)?,
+        _ => return Err(unsupported(&input)),
+    };
+
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(magnus::TryConvert));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics magnus::TryConvert for #ident #ty_generics #where_clause {
+            fn try_convert(val: magnus::Value) -> Result<Self, magnus::Error> {
+                #body
+            }
+        }
+    })
+}
+
+fn try_convert_struct(
+    container: &ContainerAttrs,
+    fields: &FieldsNamed,
+) -> Result<TokenStream, Error> {
+    let mut inits = Vec::new();
+    let mut reads_hash = false;
+    for field in &fields.named {
+        let attrs = FieldAttrs::parse(field)?;
+        let ident = field.ident.as_ref().unwrap();
+        if attrs.skip {
+            inits.push(quote! { #ident: Default::default() });
+            continue;
+        }
+        reads_hash = true;
+        let key = container.name(ident, attrs.rename);
+        let missing = match attrs.default {
+            FieldDefault::None => {
+                let msg = format!("missing key :{}", key);
+                quote! { return Err(magnus::Error::new(ruby.exception_key_error(), #msg)) }
+            }
+            FieldDefault::Default => quote! { Default::default() },
+            FieldDefault::Path(path) => quote! { #path() },
+        };
+        let context = format!(" for key :{}", key);
+        inits.push(quote! {
+            #ident: match hash.get(ruby.sym_new(#key)).or_else(|| hash.get(#key)) {
+                Some(v) => magnus::TryConvert::try_convert(v)
+                    .map_err(|e| e.map_message(|m| format!("{}{}", m, #context)))?,
+                None => #missing,
+            }
+        });
+    }
+
+    // if every field is skipped the hash is never read, but `val` should still
+    // be checked to be a hash
+    let bindings = if reads_hash {
+        quote! {
+            let ruby = magnus::Ruby::get_with(val);
+            let hash = <magnus::RHash as magnus::TryConvert>::try_convert(val)?;
+        }
+    } else {
+        quote! {
+            <magnus::RHash as magnus::TryConvert>::try_convert(val)?;
+        }
+    };
+
+    Ok(quote! {
+        #bindings
+        Ok(Self {
+            #(#inits,)*
+        })
+    })
+}
+
+fn try_convert_enum(container: &ContainerAttrs, data: &DataEnum) -> Result<TokenStream, Error> {
+    let variants = unit_variants(container, data)?;
+    let len = variants.len();
+    let names = variants.iter().map(|(_, name)| name).collect::<Vec<_>>();
+    let mut symbol_arms = Vec::new();
+    let mut string_arms = Vec::new();
+    for (i, (ident, name)) in variants.iter().enumerate() {
+        symbol_arms.push(quote! {
+            if *IDS[#i] == sym {
+                return Ok(Self::#ident);
+            }
+        });
+        let bytes = LitByteStr::new(name.as_bytes(), Span::call_site());
+        string_arms.push(quote! { #bytes => return Ok(Self::#ident), });
+    }
+    let strings = if container.accept_string {
+        quote! {
+            if let Some(s) = magnus::RString::from_value(val) {
+                // the slice is dropped before Ruby can modify or GC the string
+                match unsafe { s.as_slice() } {
+                    #(#string_arms)*
+                    _ => (),
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let expected = names
+        .iter()
+        .map(|name| format!(":{}", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let msg = format!("expected one of {}, got {{}}", expected);
+    Ok(quote! {
+        static IDS: [magnus::value::LazyId; #len] = [#(magnus::value::LazyId::new(#names)),*];
+        if let Some(sym) = magnus::Symbol::from_value(val) {
+            #(#symbol_arms)*
+        }
+        #strings
+        Err(magnus::Error::new(
+            magnus::Ruby::get_with(val).exception_arg_error(),
+            format!(#msg, magnus::value::ReprValue::inspect(val)),
+        ))
+    })
+}
+
+pub fn expand_derive_into_value(input: DeriveInput) -> Result<TokenStream, Error> {
+    let container = ContainerAttrs::parse(&input)?;
+    let body = match input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => into_value_struct(&container, fields)?,
+        Data::Enum(ref data) => into_value_enum(&container, data)?,
+        _ => return Err(unsupported(&input)),
+    };
+
+    let ident = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(magnus::IntoValue));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics magnus::IntoValue for #ident #ty_generics #where_clause {
+            fn into_value_with(self, handle: &magnus::Ruby) -> magnus::Value {
+                #body
+            }
+        }
+    })
+}
+
+fn into_value_struct(
+    container: &ContainerAttrs,
+    fields: &FieldsNamed,
+) -> Result<TokenStream, Error> {
+    let mut sets = Vec::new();
+    for field in &fields.named {
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let key = container.name(ident, attrs.rename);
+        let key = if container.string_keys {
+            quote! { #key }
+        } else {
+            quote! { handle.sym_new(#key) }
+        };
+        sets.push(quote! { let _ = hash.aset(#key, self.#ident); });
+    }
+
+    Ok(quote! {
+        let hash = handle.hash_new();
+        #(#sets)*
+        magnus::IntoValue::into_value_with(hash, handle)
+    })
+}
+
+fn into_value_enum(container: &ContainerAttrs, data: &DataEnum) -> Result<TokenStream, Error> {
+    let variants = unit_variants(container, data)?;
+    let len = variants.len();
+    let names = variants.iter().map(|(_, name)| name);
+    let arms = variants
+        .iter()
+        .enumerate()
+        .map(|(i, (ident, _))| quote! { Self::#ident => &IDS[#i], });
+    Ok(quote! {
+        static IDS: [magnus::value::LazyId; #len] = [#(magnus::value::LazyId::new(#names)),*];
+        let id: &magnus::value::LazyId = match self {
+            #(#arms)*
+        };
+        magnus::IntoValue::into_value_with(magnus::value::LazyId::get_inner_with(id, handle), handle)
+    })
+}
@@ -0,0 +1,68 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Error, ExprPath, LitStr};
+
+use crate::util;
+
+/// Parses the `class = "..."` path from a `#[magnus(...)]` attribute.
+fn parse_class(attrs: &[Attribute]) -> Result<Option<ExprPath>, Error> {
+    let mut class = None;
+    if let Some(attr) = util::get_magnus_attrubute(attrs)? {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("class") {
+                class = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+    Ok(class)
+}
+
+pub fn expand_derive_into_ruby_error(input: DeriveInput) -> Result<TokenStream, Error> {
+    let default = match parse_class(&input.attrs)? {
+        Some(path) => quote! { #path(handle) },
+        None => quote! { handle.exception_runtime_error() },
+    };
+    let class = match input.data {
+        Data::Struct(_) => default,
+        Data::Enum(ref data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                if let Some(path) = parse_class(&variant.attrs)? {
+                    let ident = &variant.ident;
+                    arms.push(quote! { Self::#ident { .. } => #path(handle), });
+                }
+            }
+            if arms.is_empty() {
+                default
+            } else {
+                quote! {
+                    match &self {
+                        #(#arms)*
+                        _ => #default,
+                    }
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "can only be derived for structs or enums",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics magnus::error::IntoRubyError for #ident #ty_generics #where_clause {
+            #[allow(unreachable_patterns)]
+            fn into_ruby_error(self, handle: &magnus::Ruby) -> magnus::Error {
+                let class = #class;
+                magnus::Error::from_std_error(class, &self)
+            }
+        }
+    })
+}
@@ -2,7 +2,11 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{Error, ItemFn};
 
-pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error> {
+pub fn expand(
+    name: Option<String>,
+    ractor_safe: bool,
+    input: ItemFn,
+) -> Result<TokenStream, Error> {
     let crate_name = match name {
         Some(v) => v,
         None => match std::env::var("CARGO_PKG_NAME") {
@@ -21,6 +25,11 @@ pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error>
         Span::call_site(),
     );
     let init_name = input.sig.ident.clone();
+    let ractor_safe = ractor_safe.then(|| {
+        quote! {
+            magnus::Ruby::get_unchecked().ext_ractor_safe(true);
+        }
+    });
 
     Ok(quote! {
         #input
@@ -29,6 +38,7 @@ pub fn expand(name: Option<String>, input: ItemFn) -> Result<TokenStream, Error>
         #[no_mangle]
         pub unsafe extern "C" fn #extern_init_name() {
             use magnus::method::{Init, RubyInit};
+            #ractor_safe
             #init_name.call_handle_error()
         }
     })
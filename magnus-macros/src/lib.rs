@@ -19,7 +19,10 @@
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
+mod convert;
+mod error;
 mod init;
+mod methods;
 mod typed_data;
 mod util;
 
@@ -35,6 +38,10 @@ mod util;
 ///   This default's to the current crate's name. The name will be prepended
 ///   with `Init_` and `-` will be replaced with `_`. This (minus the `Init_`
 ///   prefix) must match the name of the final `.so`/`.bundle` file.
+/// * `ractor_safe` - declares the extension as Ractor safe, allowing the
+///   methods it defines to be called from non-main Ractors. Only set this if
+///   all the extension's methods are safe to call from any Ractor. This has
+///   no effect on Ruby versions before 3.0.
 ///
 /// # Examples
 ///
@@ -89,21 +96,149 @@ mod util;
 ///     ()
 /// }
 /// ```
+/// Declaring the extension Ractor safe.
+/// ```
+/// fn double(i: i64) -> i64 {
+///     i * 2
+/// }
+///
+/// #[magnus::init(ractor_safe)]
+/// fn init(ruby: &magnus::Ruby) {
+///     ruby.define_global_function("double", magnus::function!(double, 1));
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let mut name = None;
+    let mut ractor_safe = false;
     if !attrs.is_empty() {
         let attr_parser = syn::meta::parser(|meta| {
             if meta.path.is_ident("name") {
                 name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
                 Ok(())
+            } else if meta.path.is_ident("ractor_safe") {
+                ractor_safe = true;
+                Ok(())
             } else {
                 Err(meta.error("unsupported attribute"))
             }
         });
         parse_macro_input!(attrs with attr_parser);
     }
-    match init::expand(name, parse_macro_input!(item)) {
+    match init::expand(name, ractor_safe, parse_macro_input!(item)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Generate a function to define the methods in an `impl` block as Ruby
+/// methods.
+///
+/// The attribute adds a `define_methods` function to the type, which takes a
+/// class or module and binds each function from the `impl` block as a Ruby
+/// method. The arity of each method is inferred from the function signature,
+/// not counting a leading `&Ruby` argument. A function taking a single slice
+/// argument (`&[Value]`) is bound with an arity of `-1`.
+///
+/// * Functions taking `&self` are defined as instance methods.
+/// * A function named `new`, or marked `#[magnus(singleton)]`, is defined as a
+///   singleton method (e.g. `Point.new`).
+/// * Other functions are defined as instance methods, with their first
+///   argument receiving `self` from Ruby, as with `magnus::method!`.
+///
+/// `impl` blocks with generics are not supported.
+///
+/// # Attributes
+///
+/// * `fn_name = "..."` - sets the name of the generated function. Defaults to
+///   `define_methods`. This is needed when using the attribute on more than
+///   one `impl` block for the same type.
+///
+/// # Method Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on functions with the following
+/// values:
+///
+/// * `name = "..."` - sets the Ruby method name. Defaults to the function's
+///   name. Can be used to define names not valid in Rust, e.g. `empty?`.
+/// * `visibility = "..."` - one of `"public"` (the default), `"private"`, or
+///   `"protected"`. Singleton methods must be public.
+/// * `singleton` - define the function as a singleton method.
+/// * `skip` - don't define the function as a Ruby method.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{class, define_module, prelude::*, Error};
+///
+/// #[magnus::wrap(class = "Euclid::Point", free_immediately, size)]
+/// struct Point {
+///     x: isize,
+///     y: isize,
+/// }
+///
+/// #[magnus::methods]
+/// impl Point {
+///     fn new(x: isize, y: isize) -> Self {
+///         Self { x, y }
+///     }
+///
+///     #[magnus(singleton)]
+///     fn origin() -> Self {
+///         Self { x: 0, y: 0 }
+///     }
+///
+///     fn x(&self) -> isize {
+///         self.x
+///     }
+///
+///     fn y(&self) -> isize {
+///         self.y
+///     }
+///
+///     #[magnus(name = "origin?")]
+///     fn is_origin(&self) -> bool {
+///         self.x == 0 && self.y == 0
+///     }
+///
+///     #[magnus(visibility = "private")]
+///     fn magnitude_squared(&self) -> isize {
+///         self.x.pow(2) + self.y.pow(2)
+///     }
+///
+///     #[magnus(skip)]
+///     fn swap(&self) -> Self {
+///         Self {
+///             x: self.y,
+///             y: self.x,
+///         }
+///     }
+/// }
+///
+/// #[magnus::init]
+/// fn init() -> Result<(), Error> {
+///     let module = define_module("Euclid")?;
+///     let class = module.define_class("Point", class::object())?;
+///     Point::define_methods(class)?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn methods(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    let mut fn_name = None;
+    if !attrs.is_empty() {
+        let attr_parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("fn_name") {
+                fn_name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        });
+        parse_macro_input!(attrs with attr_parser);
+    }
+    match methods::expand(fn_name, parse_macro_input!(item)) {
         Ok(tokens) => tokens,
         Err(e) => e.into_compile_error(),
     }
@@ -126,11 +261,21 @@ pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   implmentation does not call Ruby.
 /// * `size` - Report the [`std::mem::size_of_val`] of the type to Ruby, used
 ///   to aid in deciding when to run the garbage collector.
+/// * `mark_fields` - Mark the Ruby values stored in the type's fields, see
+///   [`DataTypeFunctions`](derive@DataTypeFunctions).
 /// * `unsafe_generics` - The derived implementation of [`TypedData`] is not
 ///   guaranteed to be correct for types with generics. If you are sure it is
 ///   for your type this attribute can be used to override the compile time
 ///   error usually generated for types with generics.
 ///
+/// # Field Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on struct fields with the
+/// following values:
+///
+/// * `skip_mark` - Don't mark this field. Required for fields that don't
+///   contain Ruby values when `mark_fields` is set.
+///
 /// # Variant Attributes
 ///
 /// The `#[magnus(...)]` attribute can be set on enum variants with the
@@ -216,9 +361,57 @@ pub fn wrap(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// For cases where no custom `DataTypeFunctions` are required a default
 /// implementation can be derived. The [`macro@wrap`] macro may be a simpler
 /// alternative in this use case.
-#[proc_macro_derive(DataTypeFunctions)]
+///
+/// When the `mark_fields` flag is set with the `#[magnus(...)]` attribute used
+/// by [`TypedData`], `DataTypeFunctions::mark` is implemented to mark every
+/// field of the type with `gc::Marker::mark`. Fields must implement
+/// `gc::Mark`, or be opted out with `#[magnus(skip_mark)]`. Without
+/// `mark_fields` the default (empty) `mark` is used, as before.
+///
+/// If the `compact` flag is also set, fields are marked with
+/// `gc::Marker::mark_movable`, and `DataTypeFunctions::compact` is implemented
+/// to update each field with `gc::Compactor::location`. As `compact` only
+/// receives `&self`, marked fields must be wrapped in a [`Cell`](std::cell::Cell)
+/// so they can be updated.
+///
+/// Fields of type `gc::WbCell` are always marked with `gc::Marker::mark`, and
+/// are not moved by compaction.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{gc, value::Opaque, DataTypeFunctions, TypedData, Value};
+///
+/// #[derive(DataTypeFunctions, TypedData)]
+/// #[magnus(class = "Pair", free_immediately, mark_fields)]
+/// struct Pair {
+///     left: Opaque<Value>,
+///     right: Opaque<Value>,
+///     #[magnus(skip_mark)]
+///     label: String,
+/// }
+/// ```
+///
+/// With compaction:
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use magnus::{value::Opaque, DataTypeFunctions, TypedData, Value};
+///
+/// #[derive(DataTypeFunctions, TypedData)]
+/// #[magnus(class = "Boxed", mark_fields, compact)]
+/// struct Boxed {
+///     inner: Cell<Opaque<Value>>,
+/// }
+/// ```
+#[proc_macro_derive(DataTypeFunctions, attributes(magnus))]
 pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
-    typed_data::expand_derive_data_type_functions(parse_macro_input!(input)).into()
+    match typed_data::expand_derive_data_type_functions(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
 }
 
 /// Derives `TypedData`, allowing the type to be passed to Ruby automatically
@@ -238,6 +431,9 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   been garbage collected. This is only safe to set if the type's [`Drop`]
 ///   and `DataTypeFunctions::free` implementations do not call Ruby.
 /// * `mark` - Enable Ruby calling the `DataTypeFunctions::mark` function.
+/// * `mark_fields` - As `mark`, and if `DataTypeFunctions` is derived, `mark`
+///   will be generated to mark each field, see
+///   [`DataTypeFunctions`](derive@DataTypeFunctions).
 /// * `size` - Enable Ruby calling the `DataTypeFunctions::size` function.
 /// * `compact` - Enable Ruby calling the `DataTypeFunctions::compact` function.
 /// * `wb_protected` - Enable the `wb_protected` flag.
@@ -254,6 +450,8 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///
 /// * `opaque_attr_reader` - For a Ruby value wrapped in `Opaque`, creates a
 ///   accessor method that returns the unwrapped Ruby value.
+/// * `skip_mark` - Don't mark this field when `mark_fields` is set and
+///   `DataTypeFunctions` is derived.
 ///
 /// # Variant Attributes
 ///
@@ -399,3 +597,276 @@ pub fn derive_typed_data(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Derives `TryConvert`, allowing a struct to be converted from a Ruby Hash,
+/// or a fieldless enum from a Ruby Symbol.
+///
+/// Each named field of a struct is read from the Hash by key, and converted
+/// with `TryConvert`. Keys may be either Symbols or Strings, with Symbol keys
+/// taking precedence.
+///
+/// A `KeyError` is raised if a key is missing. If a value can not be
+/// converted the error message will name the key.
+///
+/// Each variant of a fieldless enum is matched against a Symbol of the same
+/// name. The Symbols are interned once, so conversion does not allocate. An
+/// `ArgumentError` listing the expected Symbols is raised for any other value.
+///
+/// # Attributes
+///
+/// The `#[magnus(...)]` attribute can be set with the following values:
+///
+/// * `rename_all = "..."` - rename all fields/variants according to the given
+///   case convention. One of `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`,
+///   `"camelCase"`, `"snake_case"`, `"SCREAMING_SNAKE_CASE"`, or
+///   `"kebab-case"`.
+/// * `accept_string` - for enums, also accept a String matching a variant
+///   name.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `rename = "..."` - sets the Symbol name for the variant. Defaults to the
+///   variant name.
+///
+/// # Field Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on struct fields with the
+/// following values:
+///
+/// * `rename = "..."` - sets the Hash key for the field. Defaults to the
+///   field name.
+/// * `default` - if the key is missing use [`Default::default`] for the
+///   field.
+/// * `default = "..."` - if the key is missing call the named function for the
+///   field value.
+/// * `skip` - don't read the field from the Hash, always use
+///   [`Default::default`].
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, Error, Ruby, TryConvert};
+///
+/// #[derive(TryConvert)]
+/// struct Config {
+///     name: String,
+///     #[magnus(default)]
+///     verbose: bool,
+///     #[magnus(rename = "max-depth", default = "default_max_depth")]
+///     max_depth: usize,
+/// }
+///
+/// fn default_max_depth() -> usize {
+///     16
+/// }
+///
+/// fn describe(config: Config) -> String {
+///     format!(
+///         "{} (verbose: {}, max depth: {})",
+///         config.name, config.verbose, config.max_depth
+///     )
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("describe", function!(describe, 1));
+///     Ok(())
+/// }
+/// ```
+///
+/// With an enum:
+///
+/// ```
+/// use magnus::{function, Error, Ruby, TryConvert};
+///
+/// #[derive(TryConvert)]
+/// #[magnus(rename_all = "snake_case", accept_string)]
+/// enum Mode {
+///     Fast,
+///     Safe,
+///     #[magnus(rename = "paranoid")]
+///     ExtraSafe,
+/// }
+///
+/// fn retries(mode: Mode) -> usize {
+///     match mode {
+///         Mode::Fast => 0,
+///         Mode::Safe => 3,
+///         Mode::ExtraSafe => 10,
+///     }
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("retries", function!(retries, 1));
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_derive(TryConvert, attributes(magnus))]
+pub fn derive_try_convert(input: TokenStream) -> TokenStream {
+    match convert::expand_derive_try_convert(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Derives `IntoValue`, allowing a struct to be converted to a Ruby Hash, or
+/// a fieldless enum to a Ruby Symbol.
+///
+/// Each named field of a struct is converted with `IntoValue` and stored in
+/// a new Hash, keyed by the field name as a Symbol.
+///
+/// Each variant of a fieldless enum is converted to a Symbol of the same
+/// name.
+///
+/// # Attributes
+///
+/// The `#[magnus(...)]` attribute can be set with the following values:
+///
+/// * `string_keys` - for structs, use String keys rather than Symbols.
+/// * `rename_all = "..."` - rename all fields/variants according to the given
+///   case convention. See [`macro@TryConvert`] for the supported values.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `rename = "..."` - sets the Symbol name for the variant. Defaults to the
+///   variant name.
+///
+/// # Field Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on struct fields with the
+/// following values:
+///
+/// * `rename = "..."` - sets the Hash key for the field. Defaults to the
+///   field name.
+/// * `skip` - don't include the field in the Hash.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, Error, IntoValue, Ruby};
+///
+/// #[derive(IntoValue)]
+/// struct Stats {
+///     count: usize,
+///     #[magnus(rename = "average")]
+///     mean: f64,
+///     #[magnus(skip)]
+///     total: f64,
+/// }
+///
+/// fn stats(values: Vec<f64>) -> Stats {
+///     let total = values.iter().sum::<f64>();
+///     Stats {
+///         count: values.len(),
+///         mean: total / values.len() as f64,
+///         total,
+///     }
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("stats", function!(stats, 1));
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_derive(IntoValue, attributes(magnus))]
+pub fn derive_into_value(input: TokenStream) -> TokenStream {
+    match convert::expand_derive_into_value(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Derives `IntoRubyError`, allowing a Rust error type to be returned to Ruby
+/// from a function bound with `function!`/`method!`, where it will be raised
+/// as a Ruby exception.
+///
+/// The type must implement [`std::error::Error`]. The exception message is
+/// taken from the type's [`Display`](std::fmt::Display) implementation, and
+/// errors returned by [`source`](std::error::Error::source) are converted to
+/// `RuntimeError`s and set as the exception's `cause`. See
+/// `Error::from_std_error`.
+///
+/// # Attributes
+///
+/// The `#[magnus(...)]` attribute can be set with the following values:
+///
+/// * `class = "..."` - the path of a function taking `&Ruby` and returning
+///   the `ExceptionClass` to raise. Defaults to `RuntimeError`.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `class = "..."` - the path of a function taking `&Ruby` and returning
+///   the `ExceptionClass` to raise for this variant, overriding the
+///   container's `class`.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// use magnus::{exception::ExceptionClass, function, Error, IntoRubyError, Ruby};
+///
+/// #[derive(Debug, IntoRubyError)]
+/// enum ParseError {
+///     #[magnus(class = "Ruby::exception_arg_error")]
+///     Empty,
+///     #[magnus(class = "parse_error_class")]
+///     Invalid(std::num::ParseIntError),
+/// }
+///
+/// fn parse_error_class(ruby: &Ruby) -> ExceptionClass {
+///     ruby.exception_type_error()
+/// }
+///
+/// impl fmt::Display for ParseError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match self {
+///             Self::Empty => f.write_str("empty input"),
+///             Self::Invalid(_) => f.write_str("invalid input"),
+///         }
+///     }
+/// }
+///
+/// impl std::error::Error for ParseError {
+///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+///         match self {
+///             Self::Empty => None,
+///             Self::Invalid(e) => Some(e),
+///         }
+///     }
+/// }
+///
+/// fn parse(s: String) -> Result<i64, ParseError> {
+///     if s.is_empty() {
+///         return Err(ParseError::Empty);
+///     }
+///     s.parse().map_err(ParseError::Invalid)
+/// }
+///
+/// #[magnus::init]
+/// fn init(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("parse", function!(parse, 1));
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_derive(IntoRubyError, attributes(magnus))]
+pub fn derive_into_ruby_error(input: TokenStream) -> TokenStream {
+    match error::expand_derive_into_ruby_error(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
@@ -0,0 +1,212 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    ext::IdentExt, spanned::Spanned, Error, FnArg, ImplItem, ImplItemFn, ItemImpl, LitStr, Type,
+};
+
+use crate::util;
+
+enum Visibility {
+    Public,
+    Private,
+    Protected,
+}
+
+struct MethodAttrs {
+    name: Option<String>,
+    visibility: Visibility,
+    singleton: bool,
+    skip: bool,
+}
+
+impl MethodAttrs {
+    fn parse(func: &ImplItemFn) -> Result<Self, Error> {
+        let mut attrs = Self {
+            name: None,
+            visibility: Visibility::Public,
+            singleton: false,
+            skip: false,
+        };
+        let attr = match util::get_magnus_attrubute(&func.attrs)? {
+            Some(v) => v,
+            None => return Ok(attrs),
+        };
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                attrs.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("visibility") {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                attrs.visibility = match lit.value().as_str() {
+                    "public" => Visibility::Public,
+                    "private" => Visibility::Private,
+                    "protected" => Visibility::Protected,
+                    _ => {
+                        return Err(Error::new(
+                            lit.span(),
+                            r#"expected "public", "private", or "protected""#,
+                        ))
+                    }
+                };
+                Ok(())
+            } else if meta.path.is_ident("singleton") {
+                attrs.singleton = true;
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        Ok(attrs)
+    }
+}
+
+fn is_ruby_handle(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => match &*r.elem {
+            Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .map(|s| s.ident == "Ruby")
+                .unwrap_or(false),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn is_value_slice(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => matches!(&*r.elem, Type::Slice(_)),
+        _ => false,
+    }
+}
+
+pub fn expand(fn_name: Option<String>, mut input: ItemImpl) -> Result<TokenStream, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "#[magnus::methods] is not supported on impl blocks with generics",
+        ));
+    }
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "#[magnus::methods] is not supported on trait impl blocks",
+        ));
+    }
+
+    let self_ty = input.self_ty.clone();
+    let mut definitions = Vec::new();
+
+    for item in input.items.iter_mut() {
+        let func = match item {
+            ImplItem::Fn(f) => f,
+            _ => continue,
+        };
+        let attrs = MethodAttrs::parse(func)?;
+        func.attrs.retain(|a| !a.path().is_ident("magnus"));
+        if attrs.skip {
+            continue;
+        }
+
+        let ident = &func.sig.ident;
+        let name = attrs.name.unwrap_or_else(|| ident.unraw().to_string());
+        let singleton = attrs.singleton || ident == "new";
+
+        let mut inputs = func.sig.inputs.iter().peekable();
+        let mut receiver = false;
+        if let Some(FnArg::Receiver(r)) = inputs.peek() {
+            if r.reference.is_none() || r.mutability.is_some() {
+                return Err(Error::new(
+                    r.span(),
+                    "methods must take `&self` (or an explicit `rb_self` argument)",
+                ));
+            }
+            receiver = true;
+            inputs.next();
+        } else if let Some(FnArg::Typed(t)) = inputs.peek() {
+            if is_ruby_handle(&t.ty) {
+                inputs.next();
+            }
+        }
+        let args = inputs.collect::<Vec<_>>();
+
+        if singleton && receiver {
+            return Err(Error::new(
+                func.sig.span(),
+                "singleton methods can not take `self`",
+            ));
+        }
+
+        let mut args = args.as_slice();
+        if !singleton && !receiver {
+            match args.split_first() {
+                Some((_, rest)) => args = rest,
+                None => {
+                    return Err(Error::new(
+                        func.sig.span(),
+                        "methods must take `&self` or an explicit `rb_self` argument, use `#[magnus(singleton)]` for singleton methods",
+                    ))
+                }
+            }
+        }
+
+        let arity = match args {
+            [FnArg::Typed(t)] if is_value_slice(&t.ty) => -1,
+            _ if args.len() > 16 => {
+                return Err(Error::new(
+                    func.sig.span(),
+                    "methods can not take more than 16 arguments",
+                ))
+            }
+            _ => args.len() as i8,
+        };
+        let arity = proc_macro2::Literal::i8_unsuffixed(arity);
+
+        definitions.push(match (singleton, attrs.visibility) {
+            (true, Visibility::Public) => quote! {
+                module.define_singleton_method(#name, magnus::function!(<#self_ty>::#ident, #arity))?;
+            },
+            (true, _) => {
+                return Err(Error::new(
+                    func.sig.span(),
+                    "singleton methods must be public",
+                ))
+            }
+            (false, Visibility::Public) => quote! {
+                module.define_method(#name, magnus::method!(<#self_ty>::#ident, #arity))?;
+            },
+            (false, Visibility::Private) => quote! {
+                module.define_private_method(#name, magnus::method!(<#self_ty>::#ident, #arity))?;
+            },
+            (false, Visibility::Protected) => quote! {
+                module.define_protected_method(#name, magnus::method!(<#self_ty>::#ident, #arity))?;
+            },
+        });
+    }
+
+    let fn_name = Ident::new(
+        fn_name.as_deref().unwrap_or("define_methods"),
+        Span::call_site(),
+    );
+
+    Ok(quote! {
+        #input
+
+        impl #self_ty {
+            /// Define the methods from this `impl` block as Ruby methods on
+            /// `module`.
+            pub fn #fn_name<T>(module: T) -> Result<(), magnus::Error>
+            where
+                T: magnus::Module,
+            {
+                #(#definitions)*
+                Ok(())
+            }
+        }
+    })
+}
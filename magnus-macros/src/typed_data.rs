@@ -1,7 +1,8 @@
-use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, LitStr,
+    spanned::Spanned, Attribute, Data, DataEnum, DataStruct, DeriveInput, Error, Expr, Field,
+    Fields, FieldsNamed, Index, LitStr, Token, Type,
 };
 
 use crate::util;
@@ -14,12 +15,236 @@ pub fn expand(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
-pub fn expand_derive_data_type_functions(input: DeriveInput) -> TokenStream {
+#[derive(Default)]
+struct TypedDataAttrs {
+    class: Option<String>,
+    name: Option<String>,
+    mark: bool,
+    mark_fields: bool,
+    size: bool,
+    compact: bool,
+    free_immediately: bool,
+    wb_protected: bool,
+    frozen_shareable: bool,
+    unsafe_generics: bool,
+}
+
+impl TypedDataAttrs {
+    fn parse(attrs: &Attribute) -> Result<Self, Error> {
+        let mut this = Self::default();
+        attrs.parse_nested_meta(|meta| {
+            if meta.path.is_ident("class") {
+                this.class = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                this.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("mark") {
+                this.mark = true;
+                Ok(())
+            } else if meta.path.is_ident("mark_fields") {
+                this.mark_fields = true;
+                Ok(())
+            } else if meta.path.is_ident("size") {
+                this.size = true;
+                Ok(())
+            } else if meta.path.is_ident("compact") {
+                this.compact = true;
+                Ok(())
+            } else if meta.path.is_ident("free_immediately") {
+                this.free_immediately = true;
+                Ok(())
+            } else if meta.path.is_ident("wb_protected") {
+                this.wb_protected = true;
+                Ok(())
+            } else if meta.path.is_ident("frozen_shareable") {
+                this.frozen_shareable = true;
+                Ok(())
+            } else if meta.path.is_ident("unsafe_generics") {
+                this.unsafe_generics = true;
+                Ok(())
+            } else if meta.path.is_ident("free_immediatly") {
+                Err(meta.error("unsupported attribute (use free_immediately)"))
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        Ok(this)
+    }
+}
+
+/// The container attributes used by `#[derive(DataTypeFunctions)]`.
+///
+/// Other keys are ignored, they are validated by `#[derive(TypedData)]`.
+#[derive(Default)]
+struct DataTypeFunctionsAttrs {
+    mark_fields: bool,
+    compact: bool,
+}
+
+impl DataTypeFunctionsAttrs {
+    fn parse(attrs: &Attribute) -> Result<Self, Error> {
+        let mut this = Self::default();
+        attrs.parse_nested_meta(|meta| {
+            if meta.path.is_ident("mark_fields") {
+                this.mark_fields = true;
+            } else if meta.path.is_ident("compact") {
+                this.compact = true;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            }
+            Ok(())
+        })?;
+        Ok(this)
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    opaque_attr_reader: bool,
+    skip_mark: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &Field) -> Result<Self, Error> {
+        let mut this = Self::default();
+        let attrs = match util::get_magnus_attrubute(&field.attrs)? {
+            Some(v) => v,
+            None => return Ok(this),
+        };
+        attrs.parse_nested_meta(|meta| {
+            if meta.path.is_ident("opaque_attr_reader") {
+                this.opaque_attr_reader = true;
+                Ok(())
+            } else if meta.path.is_ident("skip_mark") {
+                this.skip_mark = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        Ok(this)
+    }
+}
+
+/// Returns true if `ty` looks like `magnus::gc::WbCell`, which is marked by
+/// reference and not moved by compaction.
+fn is_wb_cell(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident == "WbCell")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Generates a pattern destructuring `fields`, along with the bindings for
+/// each field that should be marked, and if that field is a `WbCell`.
+fn mark_pattern(fields: &Fields) -> Result<(TokenStream, Vec<(Ident, bool)>), Error> {
+    let mut bindings = Vec::new();
+    let mut parts = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let binding = format_ident!("__field_{}", i);
+        let attrs = FieldAttrs::parse(field)?;
+        let member = match &field.ident {
+            Some(ident) => quote! { #ident },
+            None => Index::from(i).into_token_stream(),
+        };
+        if attrs.skip_mark {
+            parts.push(quote! { #member: _ });
+        } else {
+            parts.push(quote! { #member: #binding });
+            bindings.push((binding, is_wb_cell(&field.ty)));
+        }
+    }
+    Ok((quote! { { #(#parts,)* } }, bindings))
+}
+
+pub fn expand_derive_data_type_functions(input: DeriveInput) -> Result<TokenStream, Error> {
+    let attrs = match util::get_magnus_attrubute(&input.attrs)? {
+        Some(v) => DataTypeFunctionsAttrs::parse(v)?,
+        None => DataTypeFunctionsAttrs::default(),
+    };
+
     let ident = input.ident;
     let generics = input.generics;
-    quote! {
-        impl #generics magnus::DataTypeFunctions for #ident #generics {}
+    if !attrs.mark_fields {
+        return Ok(quote! {
+            impl #generics magnus::DataTypeFunctions for #ident #generics {}
+        });
+    }
+
+    let mut mark_arms = Vec::new();
+    let mut compact_arms = Vec::new();
+    let variants = match input.data {
+        Data::Struct(DataStruct { ref fields, .. }) => vec![(quote! { Self }, fields)],
+        Data::Enum(DataEnum { ref variants, .. }) => variants
+            .iter()
+            .map(|v| {
+                let ident = &v.ident;
+                (quote! { Self::#ident }, &v.fields)
+            })
+            .collect(),
+        Data::Union(_) => {
+            return Err(Error::new(
+                ident.span(),
+                "`mark_fields` can not be used with unions",
+            ))
+        }
+    };
+    for (path, fields) in variants {
+        let (pattern, bindings) = mark_pattern(fields)?;
+        let mut marks = Vec::new();
+        let mut locates = Vec::new();
+        for (binding, wb_cell) in bindings {
+            if wb_cell {
+                marks.push(quote! { marker.mark(#binding); });
+            } else if attrs.compact {
+                marks.push(quote! { marker.mark_movable(#binding.get()); });
+                locates.push(quote! { #binding.set(compactor.location(#binding.get())); });
+            } else {
+                marks.push(quote! { marker.mark(*#binding); });
+            }
+        }
+        mark_arms.push(quote! { #path #pattern => { #(#marks)* } });
+        if attrs.compact {
+            compact_arms.push(quote! {
+                #[allow(unused_variables)]
+                #path #pattern => { #(#locates)* }
+            });
+        }
     }
+
+    let compact = if attrs.compact {
+        quote! {
+            fn compact(&self, #[allow(unused_variables)] compactor: &magnus::gc::Compactor) {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#compact_arms)*
+                    _ => (),
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #generics magnus::DataTypeFunctions for #ident #generics {
+            fn mark(&self, #[allow(unused_variables)] marker: &magnus::gc::Marker) {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#mark_arms)*
+                    _ => (),
+                }
+            }
+
+            #compact
+        }
+    })
 }
 
 pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -28,50 +253,18 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         None => return Err(Error::new(input.span(), "missing #[magnus] attribute")),
     };
 
-    let mut class = None;
-    let mut name = None;
-    let mut mark = false;
-    let mut size = false;
-    let mut compact = false;
-    let mut free_immediately = false;
-    let mut wb_protected = false;
-    let mut frozen_shareable = false;
-    let mut unsafe_generics = false;
-
-    attrs.parse_nested_meta(|meta| {
-        if meta.path.is_ident("class") {
-            class = Some(meta.value()?.parse::<LitStr>()?.value());
-            Ok(())
-        } else if meta.path.is_ident("name") {
-            name = Some(meta.value()?.parse::<LitStr>()?.value());
-            Ok(())
-        } else if meta.path.is_ident("mark") {
-            mark = true;
-            Ok(())
-        } else if meta.path.is_ident("size") {
-            size = true;
-            Ok(())
-        } else if meta.path.is_ident("compact") {
-            compact = true;
-            Ok(())
-        } else if meta.path.is_ident("free_immediately") {
-            free_immediately = true;
-            Ok(())
-        } else if meta.path.is_ident("wb_protected") {
-            wb_protected = true;
-            Ok(())
-        } else if meta.path.is_ident("frozen_shareable") {
-            frozen_shareable = true;
-            Ok(())
-        } else if meta.path.is_ident("unsafe_generics") {
-            unsafe_generics = true;
-            Ok(())
-        } else if meta.path.is_ident("free_immediatly") {
-            Err(meta.error("unsupported attribute (use free_immediately)"))
-        } else {
-            Err(meta.error("unsupported attribute"))
-        }
-    })?;
+    let TypedDataAttrs {
+        class,
+        name,
+        mark,
+        mark_fields,
+        size,
+        compact,
+        free_immediately,
+        wb_protected,
+        frozen_shareable,
+        unsafe_generics,
+    } = TypedDataAttrs::parse(attrs)?;
 
     if !input.generics.to_token_stream().is_empty() && !unsafe_generics {
         let case = if input.generics.type_params().count() > 0 {
@@ -156,19 +349,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     }) = input.data
     {
         for field in named {
-            let attrs = match util::get_magnus_attrubute(&field.attrs)? {
-                Some(v) => v,
-                None => continue,
-            };
-            let mut read = false;
-            attrs.parse_nested_meta(|meta| {
-                if meta.path.is_ident("opaque_attr_reader") {
-                    read = true;
-                    Ok(())
-                } else {
-                    Err(meta.error("unsupported attribute"))
-                }
-            })?;
+            let read = FieldAttrs::parse(field)?.opaque_attr_reader;
             let ident = field.ident.as_ref().unwrap();
             let ty = &field.ty;
             if read {
@@ -195,7 +376,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
 
     let mut builder = Vec::new();
     builder.push(quote! { magnus::data_type_builder!(#ident, #name) });
-    if mark {
+    if mark || mark_fields {
         builder.push(quote! { .mark() });
     }
     if size {
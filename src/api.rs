@@ -1,7 +1,11 @@
 //! This module/file's name is a hack to get the `impl Ruby` defined here to
 //! show first in docs. This module shouldn't be exposed publicly.
 
-use std::{cell::RefCell, marker::PhantomData};
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use rb_sys::ruby_native_thread_p;
 
@@ -16,7 +20,7 @@ extern "C" {
 use crate::{error::RubyUnavailableError, value::ReprValue};
 
 #[derive(Clone, Copy)]
-enum RubyGvlState {
+pub(crate) enum RubyGvlState {
     Locked,
     Unlocked,
     NonRubyThread,
@@ -26,8 +30,12 @@ thread_local! {
     static RUBY_GVL_STATE: RefCell<Option<RubyGvlState>> = RefCell::new(None);
 }
 
+/// Set once the extension has been declared Ractor safe, and so may be called
+/// from non-main Ractors.
+pub(crate) static RACTOR_SAFE: AtomicBool = AtomicBool::new(false);
+
 impl RubyGvlState {
-    fn current() -> Self {
+    pub(crate) fn current() -> Self {
         let current = if unsafe { ruby_thread_has_gvl_p() } != 0 {
             Self::Locked
         } else if unsafe { ruby_native_thread_p() != 0 } {
@@ -45,11 +53,19 @@ impl RubyGvlState {
         RUBY_GVL_STATE.with(|ruby_gvl_state| {
             let x = *ruby_gvl_state.borrow();
             match x {
-                // assumed not to change because there's currently no api to
-                // unlock.
-                Some(Self::Locked) => Self::Locked,
+                // the only api to unlock is `Ruby::without_gvl`, which updates
+                // the cache, so assumed not to change.
+                Some(Self::Locked) => {
+                    // with M:N threads Ruby threads in non-main Ractors may
+                    // move between native threads, so a thread local cache
+                    // can't be trusted if we can be called from a Ractor.
+                    if cfg!(ruby_gte_3_3) && RACTOR_SAFE.load(Ordering::Relaxed) {
+                        return Self::current();
+                    }
+                    Self::Locked
+                }
                 None => Self::current(),
-                // Don't expect without an api to unlock, so skip cache
+                // may have been relocked with `Ruby::with_gvl`, so skip cache
                 Some(Self::Unlocked) => Self::current(),
                 // assumed not to change
                 Some(Self::NonRubyThread) => Self::NonRubyThread,
@@ -57,6 +73,13 @@ impl RubyGvlState {
         })
     }
 
+    /// Set the cached state for the current thread, returning a guard that
+    /// will restore the previous cached state when dropped.
+    pub(crate) fn cache(self) -> RubyGvlStateGuard {
+        let prev = RUBY_GVL_STATE.with(|ruby_gvl_state| ruby_gvl_state.replace(Some(self)));
+        RubyGvlStateGuard(prev)
+    }
+
     fn ok<T>(self, value: T) -> Result<T, RubyUnavailableError> {
         match self {
             Self::Locked => Ok(value),
@@ -66,6 +89,17 @@ impl RubyGvlState {
     }
 }
 
+/// Restores the previously cached [`RubyGvlState`] on drop.
+pub(crate) struct RubyGvlStateGuard(Option<RubyGvlState>);
+
+impl Drop for RubyGvlStateGuard {
+    fn drop(&mut self) {
+        RUBY_GVL_STATE.with(|ruby_gvl_state| {
+            *ruby_gvl_state.borrow_mut() = self.0;
+        });
+    }
+}
+
 /// A handle to access Ruby's API.
 ///
 /// Using Ruby's API requires the Ruby VM to be initalised and all access to be
@@ -97,6 +131,8 @@ impl RubyGvlState {
 /// * [`Encoding`](#encoding) - string encoding
 /// * [Encoding Index](#encoding-index) - string encoding
 /// * [Errors](#errors)
+/// * [Executor](#executor) - run Rust closures on a Ruby thread from native
+///   threads
 /// * [Extracting values from `Opaque`/`Lazy`](#extracting-values-from-opaquelazy)
 /// * [`false`](#false)
 /// * [`Fiber`](#fiber)
@@ -108,10 +144,13 @@ impl RubyGvlState {
 ///   as calling the current `super` method.
 /// * [`Id`](#id) - low-level Symbol representation
 /// * [`Integer`](#integer)
+/// * [Marshal](#marshal) - serialising Ruby objects
 /// * [`Mutex`](#mutex)
 /// * [`nil`](#nil)
 /// * [`Proc`](#proc) - Ruby's blocks as objects
 /// * [`Process`](#process) - external processes
+/// * [`Queue`](#queue) - passing values between threads
+/// * [Ractor](#ractor) - sharing objects between Ractors
 /// * [`Range`](#range)
 /// * [`RArray`](#rarray)
 /// * [`RbEncoding`](#rbencoding) - string encoding
@@ -148,6 +187,10 @@ impl Ruby {
     /// thread is a Ruby thread.
     ///
     /// If the Ruby API is not useable, returns `Err(RubyUnavailableError)`.
+    ///
+    /// This can be called from any Ractor, but note that Ruby values must not
+    /// be shared between Ractors unless they are
+    /// [shareable](Ruby::is_shareable).
     pub fn get() -> Result<Self, RubyUnavailableError> {
         RubyGvlState::cached().ok(Self(PhantomData))
     }
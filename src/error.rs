@@ -2,18 +2,25 @@
 //!
 //! See also [`Ruby`](Ruby#errors) for more error related methods.
 
-use std::{any::Any, borrow::Cow, ffi::CString, fmt, mem::transmute, os::raw::c_int};
+use std::{
+    any::Any, borrow::Cow, ffi::CString, fmt, io, mem::transmute, os::raw::c_int,
+    panic::AssertUnwindSafe,
+};
 
 use rb_sys::{
-    rb_bug, rb_ensure, rb_errinfo, rb_exc_raise, rb_iter_break_value, rb_jump_tag, rb_protect,
-    rb_set_errinfo, rb_warning, ruby_special_consts, VALUE,
+    rb_bug, rb_catch_obj, rb_ensure, rb_errinfo, rb_exc_raise, rb_iter_break_value, rb_ivar_set,
+    rb_jump_tag, rb_protect, rb_set_errinfo, rb_syserr_new_str, rb_throw_obj, rb_warning,
+    ruby_special_consts, VALUE,
 };
 
 use crate::{
     class::Class,
     exception::Exception,
-    into_value::IntoValue,
+    into_value::{ArgList, IntoValue},
     module::Module,
+    symbol::IntoSymbol,
+    try_convert::TryConvert,
+    typed_data::TypedData,
     value::{private::ReprValue as _, ReprValue, Value},
     ExceptionClass, Ruby,
 };
@@ -83,18 +90,636 @@ impl Ruby {
         .unwrap_err()
     }
 
+    /// Call `func`, returning early with a value if `func` (or any Ruby code
+    /// it calls) throws `tag`.
+    ///
+    /// `func` is passed `tag`. If `func` returns normally its return value is
+    /// returned, if `tag` is thrown the thrown value is returned. Throws of
+    /// other tags are passed through as an `Error`.
+    ///
+    /// This is equivalent to Ruby's `catch(tag) { |tag| ... }`.
+    ///
+    /// See also [`Ruby::throw`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let res: i64 = ruby.catch(ruby.to_symbol("done"), |ruby, tag| {
+    ///         for i in 1.. {
+    ///             if i * i > 50 {
+    ///                 return Err(ruby.throw(tag, i));
+    ///             }
+    ///         }
+    ///         Ok(0)
+    ///     })?;
+    ///     assert_eq!(res, 8);
+    ///
+    ///     let res: String = ruby.catch(ruby.to_symbol("done"), |ruby, _tag| {
+    ///         ruby.eval::<magnus::Value>(r#"throw :done, "from ruby""#)
+    ///     })?;
+    ///     assert_eq!(res, "from ruby");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn catch<T, F, R, U>(&self, tag: T, func: F) -> Result<U, Error>
+    where
+        T: IntoValue,
+        F: FnOnce(&Ruby, Value) -> Result<R, Error>,
+        R: IntoValue,
+        U: TryConvert,
+    {
+        unsafe extern "C" fn call<F, R>(
+            yielded_arg: VALUE,
+            callback_arg: VALUE,
+            _argc: c_int,
+            _argv: *const VALUE,
+            _blockarg: VALUE,
+        ) -> VALUE
+        where
+            F: FnOnce(&Ruby, Value) -> Result<R, Error>,
+            R: IntoValue,
+        {
+            let handle = Ruby::get_unchecked();
+            let closure = (*(callback_arg as *mut Option<F>)).take().unwrap();
+            let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                (closure)(&handle, Value::new(yielded_arg)).map(|v| handle.into_value(v))
+            })) {
+                Ok(v) => v,
+                Err(e) => Err(Error::from_panic(e)),
+            };
+            match res {
+                Ok(v) => v.as_rb_value(),
+                Err(e) => raise(e),
+            }
+        }
+
+        let tag = self.into_value(tag);
+        let mut some_func = Some(func);
+        let closure = &mut some_func as *mut Option<F> as VALUE;
+        let call_func =
+            call::<F, R> as unsafe extern "C" fn(VALUE, VALUE, c_int, *const VALUE, VALUE) -> VALUE;
+        protect(|| unsafe { Value::new(rb_catch_obj(tag.as_rb_value(), Some(call_func), closure)) })
+            .and_then(U::try_convert)
+    }
+
+    /// Create a new error that will throw `val` to the enclosing `catch` for
+    /// `tag` when returned to Ruby.
+    ///
+    /// This is equivalent to Ruby's `throw(tag, val)`. If there is no
+    /// matching `catch` the returned error will be an `UncaughtThrowError`
+    /// exception.
+    ///
+    /// See also [`Ruby::catch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn stop(ruby: &Ruby, val: i64) -> Result<(), Error> {
+    ///     Err(ruby.throw(ruby.to_symbol("stop"), val))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("stop", function!(stop, 1));
+    ///
+    ///     rb_assert!(ruby, "catch(:stop) { stop(42); 0 } == 42");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn throw<T, U>(&self, tag: T, val: U) -> Error
+    where
+        T: IntoValue,
+        U: IntoValue,
+    {
+        let tag = self.into_value(tag);
+        let val = self.into_value(val);
+        protect(|| {
+            unsafe { rb_throw_obj(tag.as_rb_value(), val.as_rb_value()) };
+            // we never get here, but this is needed to satisfy the type system
+            #[allow(unreachable_code)]
+            self.qnil()
+        })
+        .unwrap_err()
+    }
+
+    /// Call `func`, then always call `ensure`, even if `func` returned an
+    /// error, or threw or broke out of a block.
+    ///
+    /// If `ensure` returns an error that error is returned in place of the
+    /// result of `func`.
+    ///
+    /// This is equivalent to Ruby's `begin; ...; ensure; ...; end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cleaned_up = Cell::new(false);
+    ///
+    ///     let res = ruby.ensure(
+    ///         |ruby| ruby.eval::<i64>("raise 'oops'"),
+    ///         |_ruby| {
+    ///             cleaned_up.set(true);
+    ///             Ok(())
+    ///         },
+    ///     );
+    ///     assert!(res.is_err());
+    ///     assert!(cleaned_up.get());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn ensure<F, E, T>(&self, func: F, ensure: E) -> Result<T, Error>
+    where
+        F: FnOnce(&Ruby) -> Result<T, Error>,
+        E: FnOnce(&Ruby) -> Result<(), Error>,
+    {
+        // all calls in to Ruby are protected, so errors and non-local exits
+        // from Ruby arrive as a `Result`. Only a panic needs special handling
+        let res = std::panic::catch_unwind(AssertUnwindSafe(|| func(self)));
+        let ensure_res = ensure(self);
+        match res {
+            Ok(res) => ensure_res.and(res),
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+
+    /// Call `func`, and if it returns an error that is an instance of one of
+    /// `classes` (or their subclasses) call `handler` with the exception.
+    ///
+    /// Only exception classes are matched, `classes` are compared against
+    /// the class of the error (including errors created in Rust with
+    /// [`Error::new`]). Errors that do not match any of `classes` are
+    /// returned unchanged, and non-exception errors, such as the jump tags
+    /// for a `throw` or `break`, always pass through without calling
+    /// `handler`.
+    ///
+    /// This is equivalent to Ruby's `begin; ...; rescue Class1, Class2 => e;
+    /// ...; end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let res: i64 = ruby.rescue(
+    ///         |ruby| ruby.eval("Integer('nope')"),
+    ///         &[ruby.exception_arg_error(), ruby.exception_type_error()],
+    ///         |_ruby, _e| Ok(0),
+    ///     )?;
+    ///     assert_eq!(res, 0);
+    ///
+    ///     let res = ruby.rescue(
+    ///         |ruby| ruby.eval::<i64>("raise IOError"),
+    ///         &[ruby.exception_arg_error()],
+    ///         |_ruby, _e| Ok(0),
+    ///     );
+    ///     assert!(res.unwrap_err().is_kind_of(ruby.exception_io_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn rescue<F, H, T>(
+        &self,
+        func: F,
+        classes: &[ExceptionClass],
+        handler: H,
+    ) -> Result<T, Error>
+    where
+        F: FnOnce(&Ruby) -> Result<T, Error>,
+        H: FnOnce(&Ruby, Exception) -> Result<T, Error>,
+    {
+        match func(self) {
+            Err(e) if classes.iter().any(|c| e.is_kind_of(*c)) => handler(self, e.exception()),
+            res => res,
+        }
+    }
+
     /// Outputs `s` to Ruby's stderr if Ruby is configured to output warnings.
     pub fn warning(&self, s: &str) {
         let s = CString::new(s).unwrap();
         unsafe { rb_warning(s.as_ptr()) };
     }
+
+    /// Create a new error that will raise a `KeyError` with `msg`, and the
+    /// `receiver` and `key` that caused the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn fetch(ruby: &Ruby, hash: RHash, key: String) -> Result<i64, Error> {
+    ///     hash.lookup::<_, Option<i64>>(key.as_str())?
+    ///         .ok_or_else(|| ruby.key_error(format!("key not found: {:?}", key), hash, key))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("fetch", function!(fetch, 2));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         h = {"a" => 1}
+    ///         begin
+    ///           fetch(h, "b")
+    ///         rescue KeyError => e
+    ///           e.key == "b" && e.receiver.equal?(h)
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn key_error<T, R, K>(&self, msg: T, receiver: R, key: K) -> Error
+    where
+        T: Into<Cow<'static, str>>,
+        R: IntoValue,
+        K: IntoValue,
+    {
+        let kwargs = crate::kwargs!(self, "receiver" => receiver, "key" => key);
+        self.exception_new(
+            self.exception_key_error(),
+            (self.str_new(&msg.into()), kwargs),
+        )
+    }
+
+    /// Create a new error that will raise a `NameError` with `msg`, and the
+    /// `name` that caused the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn lookup(ruby: &Ruby, name: String) -> Result<(), Error> {
+    ///     Err(ruby.name_error(format!("undefined setting {}", name), name.as_str()))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("lookup", function!(lookup, 1));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           lookup("verbose")
+    ///         rescue NameError => e
+    ///           e.name == :verbose
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn name_error<T, N>(&self, msg: T, name: N) -> Error
+    where
+        T: Into<Cow<'static, str>>,
+        N: IntoSymbol,
+    {
+        self.exception_new(
+            self.exception_name_error(),
+            (self.str_new(&msg.into()), name.into_symbol_with(self)),
+        )
+    }
+
+    /// Create a new error that will raise a `StopIteration`, with `result`
+    /// as the return value of the finished iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn finish(ruby: &Ruby) -> Result<(), Error> {
+    ///     Err(ruby.stop_iteration(42))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("finish", function!(finish, 0));
+    ///
+    ///     rb_assert!(ruby, "loop { finish } == 42");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn stop_iteration<T>(&self, result: T) -> Error
+    where
+        T: IntoValue,
+    {
+        let exception = match self
+            .exception_stop_iteration()
+            .new_instance((self.str_new("iteration reached an end"),))
+        {
+            Ok(e) => e,
+            Err(e) => return e,
+        };
+        // `StopIteration#result` reads the hidden (non-`@`) `result` ivar, the
+        // same as Ruby's Enumerator sets
+        unsafe {
+            rb_ivar_set(
+                exception.as_rb_value(),
+                self.intern("result").as_rb_id(),
+                result.into_value_with(self).as_rb_value(),
+            )
+        };
+        exception.into()
+    }
+
+    /// Create a new error that will raise a `SystemExit`, exiting the
+    /// process with `status` unless rescued.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn quit(ruby: &Ruby) -> Result<(), Error> {
+    ///     Err(ruby.system_exit(3, "quitting"))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("quit", function!(quit, 0));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           quit
+    ///         rescue SystemExit => e
+    ///           e.status == 3 && e.message == "quitting"
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn system_exit<T>(&self, status: i32, msg: T) -> Error
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.exception_new(
+            self.exception_system_exit(),
+            (status, self.str_new(&msg.into())),
+        )
+    }
+
+    /// Create a new error that will raise the `Errno::*` exception matching
+    /// the system error number `errno`, such as `Errno::ENOENT`.
+    ///
+    /// If `msg` is given it is appended to the system's description of the
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn missing(ruby: &Ruby) -> Result<(), Error> {
+    ///     Err(ruby.syserr_new(2, Some("config.toml")))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("missing", function!(missing, 0));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           missing
+    ///         rescue Errno::ENOENT => e
+    ///           e.message == "No such file or directory - config.toml"
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn syserr_new(&self, errno: i32, msg: Option<&str>) -> Error {
+        let msg = match msg {
+            Some(msg) => self.str_new(msg).as_value(),
+            None => self.qnil().as_value(),
+        };
+        match protect(|| unsafe {
+            Exception::from_rb_value_unchecked(rb_syserr_new_str(errno, msg.as_rb_value()))
+        }) {
+            Ok(e) => e.into(),
+            Err(e) => e,
+        }
+    }
+
+    /// Create a new error from a Rust [`std::io::Error`].
+    ///
+    /// Errors originally from Ruby (see [`RIo`](crate::io::RIo)) are converted
+    /// back to the original error. Errors from the operating system are
+    /// converted to the matching `Errno::*` exception. Other errors are
+    /// converted to an `Errno::*` exception based on their
+    /// [`ErrorKind`](std::io::ErrorKind) where possible, `EOFError` for
+    /// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof), or otherwise
+    /// `IOError`.
+    ///
+    /// `std::io::Error` also implements [`IntoRubyError`] with this function,
+    /// so can be returned directly from functions bound as Ruby methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, Ruby};
+    ///
+    /// fn read(path: String) -> Result<String, std::io::Error> {
+    ///     std::fs::read_to_string(path)
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("read", function!(read, 1));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           read("/does/not/exist")
+    ///         rescue Errno::ENOENT
+    ///           true
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     let err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "example");
+    ///     assert!(ruby
+    ///         .error_from_io_error(&err)
+    ///         .is_kind_of(ruby.module_errno().const_get::<_, magnus::RClass>("EACCES")?));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn error_from_io_error(&self, err: &io::Error) -> Error {
+        if let Some(e) = err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<crate::io::RubyIoError>())
+        {
+            return OpaqueError::into_error_with(e.error.clone(), self);
+        }
+        // Windows error codes don't match Ruby's errno values
+        #[cfg(unix)]
+        if let Some(errno) = err.raw_os_error() {
+            return self.syserr_new(errno, None);
+        }
+        let msg = err.to_string();
+        let class = match errno_name(err.kind()) {
+            Some(name) => match self.module_errno().const_get(name) {
+                Ok(class) => class,
+                Err(e) => return e,
+            },
+            None if err.kind() == io::ErrorKind::UnexpectedEof => self.exception_eof_error(),
+            None => self.exception_io_error(),
+        };
+        self.exception_new(class, (self.str_new(&msg),))
+    }
+
+    /// Create a new error that will raise an instance of `T`'s class,
+    /// wrapping `data`, with the message `msg`.
+    ///
+    /// `T`'s class must inherit from `Exception`. This allows defining
+    /// exceptions that carry Rust data, which can be exposed to Ruby by
+    /// defining methods on the class.
+    ///
+    /// The class should have its allocator undefined with
+    /// [`Class::undef_default_alloc_func`](crate::Class::undef_default_alloc_func),
+    /// otherwise an instance raised from Ruby with `raise` will not wrap any
+    /// data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, method, prelude::*, rb_assert, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "ParseError")]
+    /// struct ParseError {
+    ///     line: usize,
+    /// }
+    ///
+    /// impl ParseError {
+    ///     fn line(&self) -> usize {
+    ///         self.line
+    ///     }
+    /// }
+    ///
+    /// fn parse(ruby: &Ruby) -> Result<(), Error> {
+    ///     Err(ruby.typed_data_error(ParseError { line: 7 }, "unexpected token"))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_error("ParseError", ruby.exception_standard_error())?;
+    ///     class.undef_default_alloc_func();
+    ///     class.define_method("line", method!(ParseError::line, 0))?;
+    ///     ruby.define_global_function("parse", function!(parse, 0));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         begin
+    ///           parse
+    ///         rescue ParseError => e
+    ///           e.message == "unexpected token" && e.line == 7
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn typed_data_error<T, M>(&self, data: T, msg: M) -> Error
+    where
+        T: TypedData,
+        M: Into<Cow<'static, str>>,
+    {
+        let obj = self.wrap(data);
+        let exception = match Exception::from_value(obj.as_value()) {
+            Some(e) => e,
+            None => {
+                return Error::new(
+                    self.exception_type_error(),
+                    format!("{} is not an Exception", unsafe { obj.classname() }),
+                )
+            }
+        };
+        // the object was allocated without calling `new`, so initialise the
+        // Exception state (the message) directly
+        match exception.funcall::<_, _, Value>("initialize", (self.str_new(&msg.into()),)) {
+            Ok(_) => exception.into(),
+            Err(e) => e,
+        }
+    }
+
+    /// Returns a new instance of `class` as an `Error`, or the error raised
+    /// while creating the instance.
+    fn exception_new<T>(&self, class: ExceptionClass, args: T) -> Error
+    where
+        T: ArgList,
+    {
+        match class.new_instance(args) {
+            Ok(e) => e.into(),
+            Err(e) => e,
+        }
+    }
+}
+
+/// Returns the name of the `Errno::*` class matching `kind`, if any.
+fn errno_name(kind: io::ErrorKind) -> Option<&'static str> {
+    use io::ErrorKind::*;
+
+    Some(match kind {
+        NotFound => "ENOENT",
+        PermissionDenied => "EACCES",
+        ConnectionRefused => "ECONNREFUSED",
+        ConnectionReset => "ECONNRESET",
+        ConnectionAborted => "ECONNABORTED",
+        NotConnected => "ENOTCONN",
+        AddrInUse => "EADDRINUSE",
+        AddrNotAvailable => "EADDRNOTAVAIL",
+        BrokenPipe => "EPIPE",
+        AlreadyExists => "EEXIST",
+        WouldBlock => "EAGAIN",
+        InvalidInput => "EINVAL",
+        TimedOut => "ETIMEDOUT",
+        Interrupted => "EINTR",
+        Unsupported => "EOPNOTSUPP",
+        OutOfMemory => "ENOMEM",
+        _ => return None,
+    })
 }
 
 /// Shorthand for `std::result::Result<T, magnus::Error>`.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// The possible types of [`Error`].
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum ErrorType {
     /// An interrupt, such as `break` or `throw`.
     Jump(Tag),
@@ -150,6 +775,67 @@ impl Error {
         Self(ErrorType::Error(class, msg.into()))
     }
 
+    /// Create a new `Error` from a Rust [`std::error::Error`], that will be
+    /// raised as an instance of `class`.
+    ///
+    /// The exception message is taken from `err`'s [`Display`](fmt::Display)
+    /// implementation. Errors in `err`'s
+    /// [`source`](std::error::Error::source) chain are converted to
+    /// `RuntimeError`s, and each is set as the `cause` of the preceding
+    /// exception.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let parse_err = "x".parse::<i64>().unwrap_err();
+    ///     let err = Error::from_std_error(ruby.exception_arg_error(), &parse_err);
+    ///     assert!(err.is_kind_of(ruby.exception_arg_error()));
+    ///     assert_eq!(
+    ///         err.to_string(),
+    ///         "ArgumentError: invalid digit found in string"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn from_std_error<E>(class: ExceptionClass, err: &E) -> Self
+    where
+        E: std::error::Error + ?Sized,
+    {
+        let handle = Ruby::get_with(class);
+        let mut sources = Vec::new();
+        let mut source = err.source();
+        while let Some(e) = source {
+            sources.push(e.to_string());
+            source = e.source();
+        }
+        let cause = sources.into_iter().rev().fold(None, |cause, msg| {
+            let err = Self::new(handle.exception_runtime_error(), msg);
+            Some(match cause {
+                Some(cause) => err.with_cause(cause),
+                None => err,
+            })
+        });
+        let err = Self::new(class, err.to_string());
+        match cause {
+            Some(cause) => err.with_cause(cause),
+            None => err,
+        }
+    }
+
+    /// Returns `self` as an `Exception` with `cause` set as its cause.
+    fn with_cause(self, cause: Self) -> Self {
+        let exception = self.exception();
+        match exception.set_cause(cause.exception()) {
+            Ok(()) => exception.into(),
+            Err(e) => e,
+        }
+    }
+
     pub(crate) fn from_tag(tag: Tag) -> Self {
         Self(ErrorType::Jump(tag))
     }
@@ -245,6 +931,31 @@ impl Error {
         }
     }
 
+    /// Returns a new `Error` of the same class as `self`, with the message
+    /// rewritten by `f`.
+    ///
+    /// Used by derive macros to add context to conversion errors. `Jump`
+    /// errors are returned unchanged.
+    ///
+    /// Exceptions are copied with `Exception#exception(message)`, so the
+    /// backtrace, cause, and instance variables are kept. If the message
+    /// can't be rewritten the original error is returned.
+    #[doc(hidden)]
+    pub fn map_message<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&str) -> String,
+    {
+        match self.0 {
+            ErrorType::Jump(_) => self,
+            ErrorType::Error(class, msg) => Self::new(class, f(&msg)),
+            ErrorType::Exception(e) => e
+                .funcall::<_, _, String>("message", ())
+                .and_then(|msg| e.funcall::<_, _, Exception>("exception", (f(&msg),)))
+                .map(Self::from)
+                .unwrap_or(self),
+        }
+    }
+
     /// Consumes `self`, returning an `Exception`.
     ///
     /// # Panics
@@ -319,6 +1030,95 @@ impl From<Exception> for Error {
     }
 }
 
+/// Trait for types that can be converted to an [`Error`], and so returned to
+/// Ruby as an `Err` to raise an exception.
+///
+/// Functions bound as Ruby methods with [`function!`](crate::function!) and
+/// [`method!`](crate::method!) may return `Result<T, E>` where `E`
+/// implements this trait.
+///
+/// This trait can be derived for types implementing [`std::error::Error`]
+/// with [`#[derive(IntoRubyError)]`](macro@crate::IntoRubyError). See also
+/// [`Error::from_std_error`].
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+///
+/// use magnus::{error::IntoRubyError, function, Error, Ruby};
+///
+/// #[derive(Debug)]
+/// struct NegativeError(i64);
+///
+/// impl fmt::Display for NegativeError {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "{} is negative", self.0)
+///     }
+/// }
+///
+/// impl std::error::Error for NegativeError {}
+///
+/// impl IntoRubyError for NegativeError {
+///     fn into_ruby_error(self, handle: &Ruby) -> Error {
+///         Error::from_std_error(handle.exception_range_error(), &self)
+///     }
+/// }
+///
+/// fn checked_sqrt(i: i64) -> Result<f64, NegativeError> {
+///     if i < 0 {
+///         return Err(NegativeError(i));
+///     }
+///     Ok((i as f64).sqrt())
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("checked_sqrt", function!(checked_sqrt, 1));
+///
+///     let res: Result<f64, Error> = ruby.eval("checked_sqrt(-4)");
+///     assert!(res.unwrap_err().is_kind_of(ruby.exception_range_error()));
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait IntoRubyError {
+    /// Convert `self` into an [`Error`].
+    fn into_ruby_error(self, handle: &Ruby) -> Error;
+}
+
+impl IntoRubyError for Error {
+    #[inline]
+    fn into_ruby_error(self, _: &Ruby) -> Error {
+        self
+    }
+}
+
+impl IntoRubyError for io::Error {
+    fn into_ruby_error(self, handle: &Ruby) -> Error {
+        handle.error_from_io_error(&self)
+    }
+}
+
+impl IntoRubyError for Box<dyn std::error::Error> {
+    fn into_ruby_error(self, handle: &Ruby) -> Error {
+        Error::from_std_error(handle.exception_runtime_error(), &*self)
+    }
+}
+
+impl IntoRubyError for Box<dyn std::error::Error + Send + Sync> {
+    fn into_ruby_error(self, handle: &Ruby) -> Error {
+        Error::from_std_error(handle.exception_runtime_error(), &*self)
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl IntoRubyError for anyhow::Error {
+    fn into_ruby_error(self, handle: &Ruby) -> Error {
+        Error::from_std_error(handle.exception_runtime_error(), &*self)
+    }
+}
+
 /// A wrapper to make a [`Error`] [`Send`] + [`Sync`].
 ///
 /// [`Error`] is not [`Send`] or [`Sync`] as it provides a way to call some of
@@ -336,6 +1136,7 @@ impl From<Exception> for Error {
 /// Note that `OpaqueError` contains a Ruby value, so must be kept on the stack
 /// of a Ruby thread to prevent it from being Garbage Collected (or otherwise
 /// protected from premature GC).
+#[derive(Clone)]
 pub struct OpaqueError(ErrorType);
 
 unsafe impl Send for OpaqueError {}
@@ -373,7 +1174,7 @@ impl From<Error> for OpaqueError {
 
 /// The state of a call to Ruby exiting early, interrupting the normal flow
 /// of code.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 #[repr(i32)]
 pub enum Tag {
     // None = 0,
@@ -18,7 +18,7 @@ use rb_sys::{
 
 use crate::{
     class::{Class, RClass},
-    error::Error,
+    error::{Error, ErrorType},
     into_value::{ArgList, IntoValue},
     module::Module,
     object::Object,
@@ -85,6 +85,254 @@ impl Exception {
     pub fn exception_class(self) -> ExceptionClass {
         unsafe { ExceptionClass::from_rb_value_unchecked(self.class().as_rb_value()) }
     }
+
+    /// Returns the backtrace of `self`, or `None` if `self` has not been
+    /// raised and has no backtrace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     assert!(e.backtrace()?.is_none());
+    ///
+    ///     let e: Exception = ruby.eval(
+    ///         r#"
+    ///         def example
+    ///           raise "example"
+    ///         end
+    ///
+    ///         begin
+    ///           example
+    ///         rescue => e
+    ///           e
+    ///         end
+    ///         "#,
+    ///     )?;
+    ///     let backtrace = e.backtrace()?.unwrap();
+    ///     assert!(backtrace[0].contains(":3:in "));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn backtrace(self) -> Result<Option<Vec<String>>, Error> {
+        self.funcall("backtrace", ())
+    }
+
+    /// Returns the backtrace of `self` as a list of [`BacktraceLocation`]s,
+    /// or `None` if `self` has not been raised or its backtrace was set with
+    /// [`set_backtrace`](Exception::set_backtrace).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(
+    ///         r#"
+    ///         def example
+    ///           raise "example"
+    ///         end
+    ///
+    ///         begin
+    ///           example
+    ///         rescue => e
+    ///           e
+    ///         end
+    ///         "#,
+    ///     )?;
+    ///     let locations = e.backtrace_locations()?.unwrap();
+    ///     assert_eq!(locations[0].lineno, 3);
+    ///     assert!(locations[0].label.ends_with("example"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn backtrace_locations(self) -> Result<Option<Vec<BacktraceLocation>>, Error> {
+        let locations = match self.funcall::<_, _, Option<RArray>>("backtrace_locations", ())? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let mut res = Vec::with_capacity(locations.len());
+        for location in locations {
+            res.push(BacktraceLocation {
+                path: location.funcall("path", ())?,
+                lineno: location.funcall("lineno", ())?,
+                label: location
+                    .funcall::<_, _, Option<String>>("label", ())?
+                    .unwrap_or_default(),
+            });
+        }
+        Ok(Some(res))
+    }
+
+    /// Sets the backtrace of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     e.set_backtrace(["lib/example.rb:1:in `example'"])?;
+    ///     assert_eq!(
+    ///         e.backtrace()?.unwrap(),
+    ///         vec![String::from("lib/example.rb:1:in `example'")]
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_backtrace<I, T>(self, backtrace: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let handle = Ruby::get_with(self);
+        let ary = handle.ary_from_iter(
+            backtrace
+                .into_iter()
+                .map(|line| handle.str_new(line.as_ref())),
+        );
+        self.funcall::<_, _, Value>("set_backtrace", (ary,))?;
+        Ok(())
+    }
+
+    /// Returns the exception that was being handled when `self` was raised,
+    /// if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(
+    ///         r#"
+    ///         begin
+    ///           begin
+    ///             raise "inner"
+    ///           rescue
+    ///             raise ArgumentError, "outer"
+    ///           end
+    ///         rescue => e
+    ///           e
+    ///         end
+    ///         "#,
+    ///     )?;
+    ///     let cause = e.cause()?.unwrap();
+    ///     assert!(cause.is_kind_of(ruby.exception_runtime_error()));
+    ///     assert!(cause.cause()?.is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn cause(self) -> Result<Option<Exception>, Error> {
+        self.funcall("cause", ())
+    }
+
+    /// Sets `cause` as the cause of `self`.
+    ///
+    /// Ruby does not provide a way to directly set the cause of an
+    /// exception, so this raises and immediately rescues `self`. As a
+    /// result, if `self` has no backtrace, it will be set to the current
+    /// Ruby stack.
+    ///
+    /// Returns `Err` if Ruby rejects `cause`, for example if this would
+    /// create a circular chain of causes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cause = ruby
+    ///         .exception_runtime_error()
+    ///         .new_instance(("inner",))?;
+    ///     let e = ruby.exception_arg_error().new_instance(("outer",))?;
+    ///     e.set_cause(cause)?;
+    ///     assert!(e.cause()?.unwrap().equal(cause)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_cause(self, cause: Exception) -> Result<(), Error> {
+        let handle = Ruby::get_with(self);
+        let args = (self, crate::kwargs!(&handle, "cause" => cause));
+        match handle.module_kernel().funcall::<_, _, Value>("raise", args) {
+            Ok(_) => unreachable!("raise returned"),
+            Err(e) => match e.error_type() {
+                ErrorType::Exception(ex) if ex.as_rb_value() == self.as_rb_value() => Ok(()),
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Returns a formatted string of `self`, including its message and
+    /// backtrace, as Ruby would print for an uncaught exception.
+    ///
+    /// `highlight` sets if the output includes ANSI escape sequences to
+    /// format the text. `order` sets if the backtrace is printed with the
+    /// innermost frame first ([`BacktraceOrder::Top`]) or last
+    /// ([`BacktraceOrder::Bottom`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{exception::BacktraceOrder, Error, Exception, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let e: Exception = ruby.eval(r#"StandardError.new("example")"#)?;
+    ///     let msg = e.full_message(false, BacktraceOrder::Top)?;
+    ///     assert!(msg.contains("example (StandardError)"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn full_message(self, highlight: bool, order: BacktraceOrder) -> Result<String, Error> {
+        let handle = Ruby::get_with(self);
+        let order = match order {
+            BacktraceOrder::Top => "top",
+            BacktraceOrder::Bottom => "bottom",
+        };
+        self.funcall(
+            "full_message",
+            (crate::kwargs!(&handle, "highlight" => highlight, "order" => handle.to_symbol(order)),),
+        )
+    }
+}
+
+/// A single frame of an [`Exception`]'s backtrace.
+///
+/// See [`Exception::backtrace_locations`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BacktraceLocation {
+    /// The path of the file containing the frame.
+    pub path: String,
+    /// The line number of the frame.
+    pub lineno: usize,
+    /// The label of the frame, usually the method or block name.
+    pub label: String,
+}
+
+/// The order of backtrace frames in [`Exception::full_message`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BacktraceOrder {
+    /// The innermost frame is printed first, after the error message.
+    Top,
+    /// The innermost frame is printed last, before the error message.
+    Bottom,
 }
 
 impl fmt::Display for Exception {
@@ -98,7 +346,7 @@ impl fmt::Debug for Exception {
         if f.alternate() {
             unsafe {
                 writeln!(f, "{}: {}", self.classname(), self)?;
-                if let Ok(Some(backtrace)) = self.funcall::<_, _, Option<RArray>>("backtrace", ()) {
+                if let Ok(Some(backtrace)) = self.backtrace() {
                     for line in backtrace {
                         writeln!(f, "{}", line)?;
                     }
@@ -0,0 +1,502 @@
+//! Run Rust closures on a Ruby thread from native (non-Ruby) threads.
+//!
+//! See [`RubyExecutor`].
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    api::{Ruby, RubyGvlState},
+    error::Error,
+};
+
+type Job = Box<dyn FnOnce(&Ruby) + Send>;
+
+/// # Executor
+///
+/// Functions to create a [`RubyExecutor`].
+///
+/// See also the [`executor`](self) module.
+impl Ruby {
+    /// Create a [`RubyExecutor`], running closures on a new Ruby thread.
+    ///
+    /// The thread runs until the returned `RubyExecutor` is dropped (or
+    /// [`shutdown`](RubyExecutor::shutdown) is called) and all queued
+    /// closures have been run, or the thread is killed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let executor = ruby.executor_new();
+    ///     let task = executor.spawn(|ruby| ruby.str_new("example").len());
+    ///     assert_eq!(task.value(ruby)?, 7);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn executor_new(&self) -> RubyExecutor {
+        let queue = Arc::new(Queue::default());
+        let worker = queue.clone();
+        self.thread_create_from_fn(move |ruby| worker.run(ruby));
+        RubyExecutor { queue }
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    jobs: VecDeque<Job>,
+    shutdown: bool,
+    interrupted: bool,
+}
+
+enum Next {
+    Job(Job),
+    Interrupted,
+    Shutdown,
+}
+
+#[derive(Default)]
+struct Queue {
+    state: Mutex<QueueState>,
+    cond: Condvar,
+}
+
+impl Queue {
+    fn push(&self, job: Job) {
+        let mut state = self.state.lock().unwrap();
+        // if shut down `job` is dropped, cancelling its task
+        if !state.shutdown {
+            state.jobs.push_back(job);
+            self.cond.notify_one();
+        }
+    }
+
+    fn shutdown(&self) {
+        self.state.lock().unwrap().shutdown = true;
+        self.cond.notify_one();
+    }
+
+    fn interrupt(&self) {
+        self.state.lock().unwrap().interrupted = true;
+        self.cond.notify_one();
+    }
+
+    fn next(&self) -> Next {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.interrupted {
+                state.interrupted = false;
+                return Next::Interrupted;
+            }
+            if let Some(job) = state.jobs.pop_front() {
+                return Next::Job(job);
+            }
+            if state.shutdown {
+                return Next::Shutdown;
+            }
+            state = self.cond.wait(state).unwrap();
+        }
+    }
+
+    fn run(&self, ruby: &Ruby) -> Result<(), Error> {
+        // however the thread exits, stop accepting jobs and cancel any left
+        // in the queue
+        struct Guard<'a>(&'a Queue);
+
+        impl<'a> Drop for Guard<'a> {
+            fn drop(&mut self) {
+                let jobs = {
+                    let mut state = self.0.state.lock().unwrap();
+                    state.shutdown = true;
+                    std::mem::take(&mut state.jobs)
+                };
+                drop(jobs);
+            }
+        }
+
+        let _guard = Guard(self);
+        loop {
+            // waiting with the GVL released lets other Ruby threads run, and
+            // the unblock function allows the wait to be interrupted, e.g.
+            // with Thread#kill or on exit
+            match ruby.without_gvl(|| self.next(), Some(|| self.interrupt()))? {
+                Next::Job(job) => job(ruby),
+                Next::Interrupted => (),
+                Next::Shutdown => return Ok(()),
+            }
+            ruby.thread_check_ints()?;
+        }
+    }
+}
+
+/// A handle to run Rust closures on a Ruby thread, from any thread.
+///
+/// `RubyExecutor` is [`Send`] and [`Sync`], so can be shared with native
+/// threads (e.g. a thread pool), where the Ruby API is otherwise unavailable.
+/// Closures are queued with [`spawn`](RubyExecutor::spawn), and run in order
+/// on a dedicated Ruby thread, with the GVL held. The results are returned
+/// via a [`Task`].
+///
+/// While waiting for closures the Ruby thread releases the GVL, so does not
+/// prevent other Ruby threads from running.
+///
+/// Dropping the `RubyExecutor` shuts it down, see
+/// [`shutdown`](RubyExecutor::shutdown).
+///
+/// See [`Ruby::executor_new`] to create a `RubyExecutor`.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, thread};
+///
+/// use magnus::{Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let executor = Arc::new(ruby.executor_new());
+///
+///     let handles = (0..4)
+///         .map(|i| {
+///             let executor = executor.clone();
+///             thread::spawn(move || {
+///                 executor
+///                     .spawn(move |ruby| {
+///                         let ary = ruby.ary_new();
+///                         ary.push(i).unwrap();
+///                         ary.len()
+///                     })
+///                     .join()
+///                     .unwrap()
+///             })
+///         })
+///         .collect::<Vec<_>>();
+///
+///     // the GVL must be released for the executor to run
+///     let lens = ruby.without_gvl(
+///         || {
+///             handles
+///                 .into_iter()
+///                 .map(|h| h.join().unwrap())
+///                 .collect::<Vec<_>>()
+///         },
+///         None::<fn()>,
+///     )?;
+///     assert_eq!(lens, [1, 1, 1, 1]);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct RubyExecutor {
+    queue: Arc<Queue>,
+}
+
+impl RubyExecutor {
+    /// Queue `func` to run on the executor's Ruby thread.
+    ///
+    /// `func` is passed a `Ruby` handle, and can use the Ruby API. Ruby
+    /// values can not be returned from `func`, any [`Error`] should be
+    /// converted to a Rust type, such as a `String`, to be returned.
+    ///
+    /// Returns a [`Task`] that can be used to wait for the result. If `func`
+    /// panics the panic is caught and returned by the `Task`.
+    ///
+    /// If the executor has been shut down `func` is not run, and the `Task`
+    /// will return [`TaskError::Canceled`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let executor = ruby.executor_new();
+    ///     let task = executor.spawn(|ruby| {
+    ///         ruby.eval::<i64>("1 + 2").map_err(|e| e.to_string())
+    ///     });
+    ///     assert_eq!(task.value(ruby)?, Ok(3));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn spawn<F, R>(&self, func: F) -> Task<R>
+    where
+        F: 'static + Send + FnOnce(&Ruby) -> R,
+        R: 'static + Send,
+    {
+        let slot = Arc::new(Slot::default());
+        let completer = Completer(Some(slot.clone()));
+        self.queue.push(Box::new(move |ruby| {
+            completer.complete(
+                catch_unwind(AssertUnwindSafe(|| func(ruby))).map_err(TaskError::Panicked),
+            )
+        }));
+        Task(slot)
+    }
+
+    /// Stop the executor.
+    ///
+    /// Closures already queued will still be run, but any later calls to
+    /// [`spawn`](RubyExecutor::spawn) will return a canceled `Task`. The
+    /// executor's Ruby thread exits once the queue is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{executor::TaskError, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let executor = ruby.executor_new();
+    ///     let task = executor.spawn(|_ruby| 1);
+    ///     executor.shutdown();
+    ///     assert!(matches!(executor.spawn(|_ruby| 2).join(), Err(TaskError::Canceled)));
+    ///     assert_eq!(task.value(ruby)?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn shutdown(&self) {
+        self.queue.shutdown();
+    }
+}
+
+impl Drop for RubyExecutor {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl fmt::Debug for RubyExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RubyExecutor").finish_non_exhaustive()
+    }
+}
+
+/// An error returned when a [`Task`] does not complete.
+pub enum TaskError {
+    /// The closure was not run, as the executor was shut down.
+    Canceled,
+    /// The closure panicked, with the contained payload.
+    Panicked(Box<dyn Any + Send>),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Canceled => write!(f, "task canceled"),
+            Self::Panicked(_) => write!(f, "task panicked"),
+        }
+    }
+}
+
+impl fmt::Debug for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Canceled => f.write_str("Canceled"),
+            Self::Panicked(_) => f.write_str("Panicked(..)"),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+struct SlotState<T> {
+    result: Option<Result<T, TaskError>>,
+    waker: Option<Waker>,
+    interrupted: bool,
+}
+
+impl<T> Default for SlotState<T> {
+    fn default() -> Self {
+        Self {
+            result: None,
+            waker: None,
+            interrupted: false,
+        }
+    }
+}
+
+struct Slot<T> {
+    state: Mutex<SlotState<T>>,
+    cond: Condvar,
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(SlotState::default()),
+            cond: Condvar::new(),
+        }
+    }
+}
+
+impl<T> Slot<T> {
+    fn set(&self, result: Result<T, TaskError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        self.cond.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn interrupt(&self) {
+        self.state.lock().unwrap().interrupted = true;
+        self.cond.notify_all();
+    }
+
+    // blocks until the result is available, or the wait is interrupted
+    fn wait(&self) -> Option<Result<T, TaskError>> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(result) = state.result.take() {
+                return Some(result);
+            }
+            if state.interrupted {
+                state.interrupted = false;
+                return None;
+            }
+            state = self.cond.wait(state).unwrap();
+        }
+    }
+}
+
+// completes the task with `TaskError::Canceled` if dropped without a result
+struct Completer<T>(Option<Arc<Slot<T>>>);
+
+impl<T> Completer<T> {
+    fn complete(mut self, result: Result<T, TaskError>) {
+        if let Some(slot) = self.0.take() {
+            slot.set(result);
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        if let Some(slot) = self.0.take() {
+            slot.set(Err(TaskError::Canceled));
+        }
+    }
+}
+
+/// The result of a closure run with [`RubyExecutor::spawn`].
+///
+/// The result can be waited for with [`join`](Task::join) from a native
+/// thread, with [`value`](Task::value) from a Ruby thread, or `Task` can be
+/// `.await`ed as a [`Future`].
+pub struct Task<T>(Arc<Slot<T>>);
+
+impl<T> Task<T> {
+    /// Returns whether the closure has completed, or was canceled.
+    pub fn is_finished(&self) -> bool {
+        self.0.state.lock().unwrap().result.is_some()
+    }
+
+    /// Block the current thread until the closure has completed, returning
+    /// its result.
+    ///
+    /// This is intended to be called from a native thread, or with the GVL
+    /// released. See [`value`](Task::value) to wait from a Ruby thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a Ruby thread holding the GVL (as the closure
+    /// can not run until the GVL is released, this would deadlock).
+    pub fn join(self) -> Result<T, TaskError> {
+        if let RubyGvlState::Locked = RubyGvlState::current() {
+            if !self.is_finished() {
+                panic!("Task::join called while holding the GVL, use Task::value");
+            }
+        }
+        self.0.wait().unwrap()
+    }
+
+    /// Wait for the closure to complete from a Ruby thread, returning its
+    /// result.
+    ///
+    /// The GVL is released while waiting, allowing the executor (and other
+    /// Ruby threads) to run. Waiting can be interrupted (e.g. with
+    /// `Thread#raise` or ctrl-c), in which case the error raised by the
+    /// interrupt is returned.
+    ///
+    /// Returns a `RuntimeError` if the closure was canceled.
+    ///
+    /// # Panics
+    ///
+    /// If the closure panicked the panic is resumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let executor = ruby.executor_new();
+    ///     let task = executor.spawn(|ruby| {
+    ///         ruby.thread_current().equal(ruby.thread_main()).unwrap()
+    ///     });
+    ///     assert!(!task.value(ruby)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn value(self, ruby: &Ruby) -> Result<T, Error>
+    where
+        T: Send,
+    {
+        loop {
+            let result = ruby.without_gvl(|| self.0.wait(), Some(|| self.0.interrupt()))?;
+            match result {
+                Some(Ok(v)) => return Ok(v),
+                Some(Err(TaskError::Canceled)) => {
+                    return Err(Error::new(ruby.exception_runtime_error(), "task canceled"))
+                }
+                Some(Err(TaskError::Panicked(e))) => resume_unwind(e),
+                // interrupted, but the interrupt didn't raise
+                None => (),
+            }
+        }
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = Result<T, TaskError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
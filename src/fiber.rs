@@ -1,6 +1,8 @@
 //! Types and functions for working with Ruby's Fiber class.
 
-use std::{fmt, mem::size_of, os::raw::c_int, slice};
+#[cfg(any(ruby_gte_3_2, docsrs))]
+use std::ffi::c_void;
+use std::{fmt, mem::size_of, ops::BitOr, os::raw::c_int, slice, time::Duration};
 
 #[cfg(ruby_lt_3_2)]
 use rb_sys::rb_fiber_new;
@@ -8,8 +10,13 @@ use rb_sys::rb_fiber_new;
 use rb_sys::rb_fiber_new_storage;
 use rb_sys::{
     rb_data_typed_object_wrap, rb_fiber_alive_p, rb_fiber_current, rb_fiber_raise,
-    rb_fiber_resume_kw, rb_fiber_transfer_kw, rb_fiber_yield_kw, rb_obj_is_fiber, VALUE,
+    rb_fiber_resume_kw, rb_fiber_scheduler_block, rb_fiber_scheduler_current,
+    rb_fiber_scheduler_get, rb_fiber_scheduler_io_wait, rb_fiber_scheduler_io_wait_readable,
+    rb_fiber_scheduler_io_wait_writable, rb_fiber_scheduler_kernel_sleep,
+    rb_fiber_scheduler_unblock, rb_fiber_transfer_kw, rb_fiber_yield_kw, rb_obj_is_fiber, VALUE,
 };
+#[cfg(any(ruby_gte_3_2, docsrs))]
+use rb_sys::{rb_fiber_scheduler_io_read_memory, rb_fiber_scheduler_io_write_memory};
 
 #[cfg(any(ruby_gte_3_2, docsrs))]
 use crate::r_hash::RHash;
@@ -28,7 +35,7 @@ use crate::{
     typed_data::{DataType, DataTypeFunctions},
     value::{
         private::{self, ReprValue as _},
-        ReprValue, Value, QUNDEF,
+        NonZeroValue, ReprValue, Value, QUNDEF,
     },
 };
 
@@ -262,6 +269,39 @@ impl Ruby {
             .and_then(TryConvert::try_convert)
         }
     }
+
+    /// Return the Fiber scheduler for the current thread, if the current
+    /// Fiber is non-blocking and a scheduler has been set.
+    ///
+    /// Blocking operations should be delegated to the returned scheduler, so
+    /// that other Fibers may run while waiting.
+    ///
+    /// See [`Scheduler`] for an example.
+    pub fn fiber_scheduler_current(&self) -> Option<Scheduler> {
+        Scheduler::from_value(unsafe { Value::new(rb_fiber_scheduler_current()) })
+    }
+
+    /// Return the Fiber scheduler set for the current thread, if any.
+    ///
+    /// Unlike [`fiber_scheduler_current`](Ruby::fiber_scheduler_current) this
+    /// returns the scheduler even if the current Fiber is blocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.fiber_scheduler_get().is_none());
+    ///     assert!(ruby.fiber_scheduler_current().is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn fiber_scheduler_get(&self) -> Option<Scheduler> {
+        Scheduler::from_value(unsafe { Value::new(rb_fiber_scheduler_get()) })
+    }
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby's Fiber class.
@@ -510,6 +550,324 @@ impl TryConvert for Fiber {
     }
 }
 
+/// A handle to a Fiber scheduler, as set with Ruby's `Fiber.set_scheduler`.
+///
+/// A Fiber scheduler is any object implementing Ruby's `Fiber::Scheduler`
+/// interface, such as the one provided by the `async` gem. Delegating
+/// blocking operations to the scheduler allows other Fibers to run while
+/// waiting.
+///
+/// [`Ruby::thread_wait_fd`], [`Ruby::thread_fd_writable`],
+/// [`Ruby::thread_sleep`], and [`Ruby::thread_sleep_forever`] automatically
+/// use the current scheduler.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby::fiber_scheduler_current`] to get the
+/// current scheduler.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use magnus::{function, rb_assert, Error, Ruby};
+///
+/// fn nap(ruby: &Ruby) -> Result<(), Error> {
+///     match ruby.fiber_scheduler_current() {
+///         Some(scheduler) => scheduler.kernel_sleep(Some(Duration::from_millis(10))),
+///         None => ruby.thread_sleep(Duration::from_millis(10)),
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("nap", function!(nap, 0));
+///
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         class RecordingScheduler
+///           attr_reader :calls
+///
+///           def initialize
+///             @calls = []
+///           end
+///
+///           def block(blocker, timeout = nil)
+///             @calls << :block
+///           end
+///
+///           def unblock(blocker, fiber)
+///             @calls << :unblock
+///           end
+///
+///           def io_wait(io, events, timeout)
+///             @calls << :io_wait
+///             events
+///           end
+///
+///           def kernel_sleep(duration = nil)
+///             @calls << :kernel_sleep
+///           end
+///
+///           def close; end
+///         end
+///
+///         scheduler = RecordingScheduler.new
+///         Thread.new do
+///           Fiber.set_scheduler(scheduler)
+///           Fiber.new(blocking: false) { nap }.resume
+///         end.join
+///         scheduler.calls == [:kernel_sleep]
+///         "#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct Scheduler(NonZeroValue);
+
+impl Scheduler {
+    /// Return `Some(Scheduler)` if `val` is not `nil`, `None` otherwise.
+    #[inline]
+    fn from_value(val: Value) -> Option<Self> {
+        unsafe { (!val.is_nil()).then(|| Self(NonZeroValue::new_unchecked(val))) }
+    }
+
+    /// Block the current Fiber for `duration`, or indefinitely if `None`,
+    /// allowing other Fibers to run.
+    ///
+    /// Equivalent to Ruby's `sleep` when called from a non-blocking Fiber.
+    pub fn kernel_sleep(self, duration: Option<Duration>) -> Result<(), Error> {
+        let handle = Ruby::get_with(self);
+        let duration = timeout_value(&handle, duration);
+        protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_kernel_sleep(
+                self.as_rb_value(),
+                duration.as_rb_value(),
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Block the current Fiber on `blocker` until it is unblocked with
+    /// [`unblock`](Scheduler::unblock), or `timeout` passes.
+    ///
+    /// `blocker` is an arbitrary object identifying the reason for blocking,
+    /// used by the scheduler for debugging purposes.
+    pub fn block<T>(self, blocker: T, timeout: Option<Duration>) -> Result<Value, Error>
+    where
+        T: IntoValue,
+    {
+        let handle = Ruby::get_with(self);
+        let blocker = handle.into_value(blocker);
+        let timeout = timeout_value(&handle, timeout);
+        protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_block(
+                self.as_rb_value(),
+                blocker.as_rb_value(),
+                timeout.as_rb_value(),
+            ))
+        })
+    }
+
+    /// Unblock `fiber`, previously blocked on `blocker` with
+    /// [`block`](Scheduler::block).
+    ///
+    /// Unlike other methods, this may be called from a different thread to
+    /// that of the scheduler (while holding the GVL).
+    pub fn unblock<T>(self, blocker: T, fiber: Fiber) -> Result<(), Error>
+    where
+        T: IntoValue,
+    {
+        let blocker = Ruby::get_with(self).into_value(blocker);
+        protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_unblock(
+                self.as_rb_value(),
+                blocker.as_rb_value(),
+                fiber.as_rb_value(),
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Wait until `io` is ready for any of `events`, or `timeout` passes.
+    ///
+    /// Returns the events `io` is ready for, or `None` on timeout.
+    pub fn io_wait<T>(
+        self,
+        io: T,
+        events: IoEvents,
+        timeout: Option<Duration>,
+    ) -> Result<Option<IoEvents>, Error>
+    where
+        T: ReprValue,
+    {
+        let handle = Ruby::get_with(self);
+        let timeout = timeout_value(&handle, timeout);
+        let res = protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_io_wait(
+                self.as_rb_value(),
+                io.as_rb_value(),
+                handle.into_value(events.0).as_rb_value(),
+                timeout.as_rb_value(),
+            ))
+        })?;
+        if res.is_nil() || res.is_false() {
+            return Ok(None);
+        }
+        u32::try_convert(res).map(|events| Some(IoEvents(events)))
+    }
+
+    /// Wait until `io` is readable.
+    pub fn io_wait_readable<T>(self, io: T) -> Result<(), Error>
+    where
+        T: ReprValue,
+    {
+        protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_io_wait_readable(
+                self.as_rb_value(),
+                io.as_rb_value(),
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Wait until `io` is writable.
+    pub fn io_wait_writable<T>(self, io: T) -> Result<(), Error>
+    where
+        T: ReprValue,
+    {
+        protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_io_wait_writable(
+                self.as_rb_value(),
+                io.as_rb_value(),
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Read from `io` into `buf`, returning once at least `length` bytes
+    /// have been read, or at end of file.
+    ///
+    /// Returns the number of bytes read, or `None` if the scheduler does not
+    /// implement `io_read`, in which case the caller should fall back to a
+    /// regular read.
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+    pub fn io_read<T>(self, io: T, buf: &mut [u8], length: usize) -> Result<Option<usize>, Error>
+    where
+        T: ReprValue,
+    {
+        let res = protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_io_read_memory(
+                self.as_rb_value(),
+                io.as_rb_value(),
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len(),
+                length.min(buf.len()),
+            ))
+        })?;
+        self.io_result(res)
+    }
+
+    /// Write `buf` to `io`, returning once at least `length` bytes have been
+    /// written.
+    ///
+    /// Returns the number of bytes written, or `None` if the scheduler does
+    /// not implement `io_write`, in which case the caller should fall back to
+    /// a regular write.
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+    pub fn io_write<T>(self, io: T, buf: &[u8], length: usize) -> Result<Option<usize>, Error>
+    where
+        T: ReprValue,
+    {
+        let res = protect(|| unsafe {
+            Value::new(rb_fiber_scheduler_io_write_memory(
+                self.as_rb_value(),
+                io.as_rb_value(),
+                buf.as_ptr() as *const c_void,
+                buf.len(),
+                length.min(buf.len()),
+            ))
+        })?;
+        self.io_result(res)
+    }
+
+    /// Convert the result of a scheduler IO operation, which is either the
+    /// number of bytes transferred or a negated errno.
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    fn io_result(self, res: Value) -> Result<Option<usize>, Error> {
+        if res.is_undef() {
+            return Ok(None);
+        }
+        let res = isize::try_convert(res)?;
+        if res < 0 {
+            return Err(Ruby::get_with(self).syserr_new(-res as i32, None));
+        }
+        Ok(Some(res as usize))
+    }
+}
+
+impl fmt::Display for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for Scheduler {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.get()
+    }
+}
+
+impl Object for Scheduler {}
+
+unsafe impl private::ReprValue for Scheduler {}
+
+impl ReprValue for Scheduler {}
+
+/// A set of IO events, used with [`Scheduler::io_wait`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IoEvents(u32);
+
+impl IoEvents {
+    /// Readable, Ruby's `IO::READABLE`.
+    pub const READABLE: Self = Self(1);
+    /// Priority data available, Ruby's `IO::PRIORITY`.
+    pub const PRIORITY: Self = Self(2);
+    /// Writable, Ruby's `IO::WRITABLE`.
+    pub const WRITABLE: Self = Self(4);
+
+    /// Returns whether all of the events in `other` are present in `self`.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for IoEvents {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Convert an optional timeout to a Ruby Float of seconds, or `nil`.
+fn timeout_value(handle: &Ruby, timeout: Option<Duration>) -> Value {
+    handle.into_value(timeout.map(|d| d.as_secs_f64()))
+}
+
 /// Options for initialising Fiber-local storage.
 pub enum Storage {
     /// Inherit the storage from the current Fiber.
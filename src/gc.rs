@@ -2,7 +2,7 @@
 //!
 //! See also [`Ruby`](Ruby#gc) for more GC related methods.
 
-use std::{marker::PhantomData, ops::Range};
+use std::{cell::Cell, marker::PhantomData, ops::Range};
 
 use rb_sys::{
     rb_gc_adjust_memory_usage, rb_gc_count, rb_gc_disable, rb_gc_enable, rb_gc_location,
@@ -14,7 +14,8 @@ use crate::{
     error::{protect, Error},
     r_hash::RHash,
     symbol::IntoSymbol,
-    value::{private::ReprValue as _, ReprValue, Value},
+    typed_data::{Obj, TypedData},
+    value::{private::ReprValue as _, InnerValue, Opaque, ReprValue, Value},
     Ruby,
 };
 
@@ -179,6 +180,128 @@ impl Compactor {
     }
 }
 
+/// A cell for a Ruby value stored in a Rust type wrapped in a Ruby object,
+/// which runs Ruby's GC write barrier when updated.
+///
+/// Types wrapped with the `wb_protected` flag set (see
+/// [`DataTypeBuilder::wb_protected`](crate::typed_data::DataTypeBuilder::wb_protected))
+/// promise Ruby that the write barrier will be run whenever a reference to a
+/// Ruby object is stored. This allows Ruby's generational GC to avoid marking
+/// old objects on every GC run, but if the write barrier is missed a newly
+/// referenced object may be collected while still in use.
+///
+/// `WbCell` must be marked by the
+/// [`DataTypeFunctions::mark`](`crate::typed_data::DataTypeFunctions::mark`)
+/// implementation of the type containing it.
+///
+/// See also [`Obj::write_barrier`](crate::typed_data::Obj::write_barrier).
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     function, gc::{self, WbCell}, method, prelude::*, rb_assert, typed_data::Obj,
+///     DataTypeFunctions, Error, Ruby, TypedData, Value,
+/// };
+///
+/// #[derive(TypedData)]
+/// #[magnus(class = "Holder", free_immediately, mark, wb_protected)]
+/// struct Holder {
+///     item: WbCell<Value>,
+/// }
+///
+/// impl DataTypeFunctions for Holder {
+///     fn mark(&self, marker: &gc::Marker) {
+///         marker.mark(&self.item);
+///     }
+/// }
+///
+/// impl Holder {
+///     fn new(item: Value) -> Self {
+///         Self {
+///             item: WbCell::new(item),
+///         }
+///     }
+///
+///     fn item(ruby: &Ruby, rb_self: &Self) -> Value {
+///         ruby.get_inner(&rb_self.item)
+///     }
+///
+///     fn set_item(rb_self: Obj<Self>, item: Value) {
+///         rb_self.item.set(rb_self, item);
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Holder", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Holder::new, 1))?;
+///     class.define_method("item", method!(Holder::item, 0))?;
+///     class.define_method("item=", method!(Holder::set_item, 1))?;
+///
+///     rb_assert!(
+///         ruby,
+///         r#"
+///           holder = Holder.new(1)
+///           holder.item = "example"
+///           GC.start
+///           holder.item == "example"
+///         "#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct WbCell<T>(Cell<Opaque<T>>);
+
+impl<T> WbCell<T>
+where
+    T: ReprValue,
+{
+    /// Create a new `WbCell` containing `value`.
+    ///
+    /// No write barrier is run, so the `WbCell` should be created before the
+    /// Ruby object that will contain it, e.g. in the `new` function of the
+    /// wrapped type.
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(value.into()))
+    }
+
+    /// Store `value` in the cell and run the write barrier for `owner`.
+    ///
+    /// `owner` must be the Ruby object wrapping the Rust type that contains
+    /// `self`.
+    pub fn set<U>(&self, owner: Obj<U>, value: T)
+    where
+        U: TypedData,
+    {
+        self.0.set(value.into());
+        owner.write_barrier(value);
+    }
+}
+
+impl<T> InnerValue for &WbCell<T>
+where
+    T: ReprValue,
+{
+    type Value = T;
+
+    #[inline]
+    fn get_inner_with(self, ruby: &Ruby) -> Self::Value {
+        ruby.get_inner(self.0.get())
+    }
+}
+
+impl<T> private::Mark for &WbCell<T>
+where
+    T: ReprValue,
+{
+    fn raw(self) -> VALUE {
+        private::Mark::raw(self.0.get())
+    }
+}
+impl<T> Mark for &WbCell<T> where T: ReprValue {}
+
 /// Registers `value` to never be garbage collected.
 ///
 /// This is essentially a deliberate memory leak.
@@ -1,20 +1,31 @@
 use std::{
+    ffi::c_void,
     fmt,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
-    os::raw::c_long,
+    mem::size_of,
+    ops::{
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+        DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub,
+        SubAssign,
+    },
+    os::raw::{c_int, c_long},
+    ptr,
 };
 
 use rb_sys::{
-    rb_big_cmp, rb_big_div, rb_big_eq, rb_big_minus, rb_big_mul, rb_big_norm, rb_big_plus,
-    rb_int2big, rb_ll2inum, rb_to_int, rb_ull2inum, ruby_special_consts, ruby_value_type, Qtrue,
-    VALUE,
+    rb_absint_singlebit_p, rb_absint_size, rb_big2str, rb_big_and, rb_big_cmp, rb_big_div,
+    rb_big_divmod, rb_big_eq, rb_big_lshift, rb_big_minus, rb_big_modulo, rb_big_mul, rb_big_norm,
+    rb_big_or, rb_big_plus, rb_big_pow, rb_big_rshift, rb_big_xor, rb_int2big, rb_integer_pack,
+    rb_integer_unpack, rb_ll2inum, rb_str_to_inum, rb_to_int, rb_ull2inum, ruby_special_consts,
+    ruby_value_type, Qtrue, VALUE,
 };
 
 use crate::{
     error::{protect, Error},
     into_value::IntoValue,
     numeric::Numeric,
+    r_array::RArray,
     r_bignum::RBignum,
+    r_string::RString,
     try_convert::TryConvert,
     value::{
         private::{self, ReprValue as _},
@@ -22,6 +33,20 @@ use crate::{
     },
     Ruby,
 };
+#[cfg(feature = "num-bigint")]
+use crate::{into_value::IntoValueFromNative, try_convert::TryConvertOwned};
+
+// These are `#define`s in Ruby's headers, and don't appear consistently in
+// bindgen output, so define them ourselves.
+const INTEGER_PACK_MSWORD_FIRST: c_int = 0x01;
+const INTEGER_PACK_LSWORD_FIRST: c_int = 0x02;
+const INTEGER_PACK_MSBYTE_FIRST: c_int = 0x10;
+const INTEGER_PACK_LSBYTE_FIRST: c_int = 0x20;
+const INTEGER_PACK_NATIVE: c_int = 0x40;
+const INTEGER_PACK_NEGATIVE: c_int = 0x200;
+pub(crate) const INTEGER_PACK_LITTLE_ENDIAN: c_int =
+    INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_LSBYTE_FIRST;
+const INTEGER_PACK_BIG_ENDIAN: c_int = INTEGER_PACK_MSWORD_FIRST | INTEGER_PACK_MSBYTE_FIRST;
 
 pub(crate) enum IntegerType {
     Fixnum(Fixnum),
@@ -97,6 +122,206 @@ impl Ruby {
             )
         }
     }
+
+    /// Create a new `Integer` from an `i128.`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(ruby, "i == 0", i = ruby.integer_from_i128(0));
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == 170141183460469231731687303715884105727",
+    ///         i = ruby.integer_from_i128(i128::MAX),
+    ///     );
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == -170141183460469231731687303715884105728",
+    ///         i = ruby.integer_from_i128(i128::MIN),
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_i128(&self, n: i128) -> Integer {
+        if let Ok(n) = i64::try_from(n) {
+            return self.integer_from_i64(n);
+        }
+        let mut flags = INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_NATIVE;
+        if n < 0 {
+            flags |= INTEGER_PACK_NEGATIVE;
+        }
+        let abs = n.unsigned_abs();
+        unsafe {
+            self.integer_unpack(
+                &abs as *const u128 as *const c_void,
+                1,
+                size_of::<u128>(),
+                flags,
+            )
+        }
+    }
+
+    /// Create a new `Integer` from a `u128.`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(ruby, "i == 0", i = ruby.integer_from_u128(0));
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == 340282366920938463463374607431768211455",
+    ///         i = ruby.integer_from_u128(u128::MAX),
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_u128(&self, n: u128) -> Integer {
+        if let Ok(n) = u64::try_from(n) {
+            return self.integer_from_u64(n);
+        }
+        let flags = INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_NATIVE;
+        unsafe {
+            self.integer_unpack(
+                &n as *const u128 as *const c_void,
+                1,
+                size_of::<u128>(),
+                flags,
+            )
+        }
+    }
+
+    /// Create a new non-negative `Integer` from its magnitude as a byte array
+    /// in little-endian byte order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(ruby, "i == 0", i = ruby.integer_from_bytes_le(&[]));
+    ///     rb_assert!(ruby, "i == 0x0102", i = ruby.integer_from_bytes_le(&[2, 1]));
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == 2 ** 128",
+    ///         i = ruby.integer_from_bytes_le(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_bytes_le(&self, bytes: &[u8]) -> Integer {
+        unsafe {
+            self.integer_unpack(
+                bytes.as_ptr() as *const c_void,
+                bytes.len(),
+                1,
+                INTEGER_PACK_LITTLE_ENDIAN,
+            )
+        }
+    }
+
+    /// Create a new non-negative `Integer` from its magnitude as a byte array
+    /// in big-endian byte order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(ruby, "i == 0", i = ruby.integer_from_bytes_be(&[]));
+    ///     rb_assert!(ruby, "i == 0x0102", i = ruby.integer_from_bytes_be(&[1, 2]));
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == 2 ** 128",
+    ///         i = ruby.integer_from_bytes_be(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_bytes_be(&self, bytes: &[u8]) -> Integer {
+        unsafe {
+            self.integer_unpack(
+                bytes.as_ptr() as *const c_void,
+                bytes.len(),
+                1,
+                INTEGER_PACK_BIG_ENDIAN,
+            )
+        }
+    }
+
+    /// Parse an `Integer` from the string `s` in the given `radix`.
+    ///
+    /// `s` is parsed as per Ruby's `Integer(s, radix)`, so may include a
+    /// leading sign, underscores between digits, and a prefix matching
+    /// `radix` such as `0x`. Returns `Err` if `s` is not a valid integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range `2..=36`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     rb_assert!(ruby, "i == 255", i = ruby.integer_from_str_radix("ff", 16)?);
+    ///     rb_assert!(ruby, "i == -5", i = ruby.integer_from_str_radix("-101", 2)?);
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "i == 2 ** 100",
+    ///         i = ruby.integer_from_str_radix("1267650600228229401496703205376", 10)?,
+    ///     );
+    ///     assert!(ruby.integer_from_str_radix("12z", 10).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_str_radix(&self, s: &str, radix: u32) -> Result<Integer, Error> {
+        assert_radix(radix);
+        if let Ok(n) = i64::from_str_radix(s, radix) {
+            return Ok(self.integer_from_i64(n));
+        }
+        let s = self.str_new(s);
+        protect(|| unsafe {
+            Integer::from_rb_value_unchecked(rb_str_to_inum(s.as_rb_value(), radix as c_int, 1))
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `words` must point to `numwords * wordsize` readable bytes.
+    pub(crate) unsafe fn integer_unpack(
+        &self,
+        words: *const c_void,
+        numwords: usize,
+        wordsize: usize,
+        flags: c_int,
+    ) -> Integer {
+        Integer::from_rb_value_unchecked(rb_integer_unpack(
+            words,
+            numwords as _,
+            wordsize as _,
+            0,
+            flags,
+        ))
+    }
 }
 
 /// A type wrapping either a [`Fixnum`] or a [`RBignum`].
@@ -488,6 +713,383 @@ impl Integer {
         }
     }
 
+    /// Convert `self` to an `i128`. Returns `Err` if `self` is out of range
+    /// for `i128`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("0").unwrap().to_i128().unwrap(), 0);
+    /// assert_eq!(
+    ///     eval::<Integer>("170141183460469231731687303715884105727")
+    ///         .unwrap()
+    ///         .to_i128()
+    ///         .unwrap(),
+    ///     i128::MAX
+    /// );
+    /// assert_eq!(
+    ///     eval::<Integer>("-170141183460469231731687303715884105728")
+    ///         .unwrap()
+    ///         .to_i128()
+    ///         .unwrap(),
+    ///     i128::MIN
+    /// );
+    /// assert!(eval::<Integer>("170141183460469231731687303715884105728")
+    ///     .unwrap()
+    ///     .to_i128()
+    ///     .is_err());
+    /// assert!(eval::<Integer>("-170141183460469231731687303715884105729")
+    ///     .unwrap()
+    ///     .to_i128()
+    ///     .is_err());
+    /// ```
+    pub fn to_i128(self) -> Result<i128, Error> {
+        if let IntegerType::Fixnum(fix) = self.integer_type() {
+            return Ok(fix.to_i64() as i128);
+        }
+        match self.pack_u128() {
+            (0 | 1, abs) if abs <= i128::MAX as u128 => Ok(abs as i128),
+            (-1, abs) if abs <= i128::MIN.unsigned_abs() => Ok((abs as i128).wrapping_neg()),
+            _ => Err(Error::new(
+                Ruby::get_with(self).exception_range_error(),
+                "integer too big to convert into `i128`",
+            )),
+        }
+    }
+
+    /// Convert `self` to a `u128`. Returns `Err` if `self` is negative or out
+    /// of range for `u128`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("0").unwrap().to_u128().unwrap(), 0);
+    /// assert_eq!(
+    ///     eval::<Integer>("340282366920938463463374607431768211455")
+    ///         .unwrap()
+    ///         .to_u128()
+    ///         .unwrap(),
+    ///     u128::MAX
+    /// );
+    /// assert!(eval::<Integer>("-1").unwrap().to_u128().is_err());
+    /// assert!(eval::<Integer>("340282366920938463463374607431768211456")
+    ///     .unwrap()
+    ///     .to_u128()
+    ///     .is_err());
+    /// ```
+    pub fn to_u128(self) -> Result<u128, Error> {
+        if let IntegerType::Fixnum(fix) = self.integer_type() {
+            return fix.to_u64().map(u128::from);
+        }
+        let handle = Ruby::get_with(self);
+        match self.pack_u128() {
+            (0 | 1, abs) => Ok(abs),
+            (-2 | -1, _) => Err(Error::new(
+                handle.exception_range_error(),
+                "can't convert negative integer to unsigned",
+            )),
+            _ => Err(Error::new(
+                handle.exception_range_error(),
+                "integer too big to convert into `u128`",
+            )),
+        }
+    }
+
+    /// Returns the sign (as per `rb_integer_pack`) and absolute value of
+    /// `self`, truncated to 128 bits.
+    fn pack_u128(self) -> (c_int, u128) {
+        let mut abs = 0u128;
+        let sign = unsafe {
+            rb_integer_pack(
+                self.as_rb_value(),
+                &mut abs as *mut u128 as *mut c_void,
+                1,
+                size_of::<u128>() as _,
+                0,
+                INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_NATIVE,
+            )
+        };
+        (sign, abs)
+    }
+
+    /// Returns the magnitude of `self` as a byte array in little-endian byte
+    /// order.
+    ///
+    /// The returned bytes do not encode the sign of `self`. For zero, a
+    /// single zero byte is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("0").unwrap().to_bytes_le(), [0]);
+    /// assert_eq!(eval::<Integer>("0x0102").unwrap().to_bytes_le(), [2, 1]);
+    /// assert_eq!(eval::<Integer>("-0x0102").unwrap().to_bytes_le(), [2, 1]);
+    /// ```
+    pub fn to_bytes_le(self) -> Vec<u8> {
+        self.pack_bytes(INTEGER_PACK_LITTLE_ENDIAN).1
+    }
+
+    /// Returns the magnitude of `self` as a byte array in big-endian byte
+    /// order.
+    ///
+    /// The returned bytes do not encode the sign of `self`. For zero, a
+    /// single zero byte is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("0").unwrap().to_bytes_be(), [0]);
+    /// assert_eq!(eval::<Integer>("0x0102").unwrap().to_bytes_be(), [1, 2]);
+    /// assert_eq!(eval::<Integer>("-0x0102").unwrap().to_bytes_be(), [1, 2]);
+    /// ```
+    pub fn to_bytes_be(self) -> Vec<u8> {
+        self.pack_bytes(INTEGER_PACK_BIG_ENDIAN).1
+    }
+
+    /// Returns the sign (as per `rb_integer_pack`) and magnitude of `self`
+    /// as bytes, with byte order determined by `flags`.
+    pub(crate) fn pack_bytes(self, flags: c_int) -> (c_int, Vec<u8>) {
+        unsafe {
+            let len = (rb_absint_size(self.as_rb_value(), ptr::null_mut()) as usize).max(1);
+            let mut bytes = vec![0; len];
+            let sign = rb_integer_pack(
+                self.as_rb_value(),
+                bytes.as_mut_ptr() as *mut c_void,
+                len as _,
+                1,
+                0,
+                flags,
+            );
+            (sign, bytes)
+        }
+    }
+
+    /// Returns the quotient and modulus of `self` divided by `other`.
+    ///
+    /// As with Ruby's `Integer#divmod`, the quotient is rounded towards
+    /// negative infinity, and so the modulus has the same sign as `other`.
+    /// Returns `Err` if `other` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// let a = eval::<Integer>("-7").unwrap();
+    /// let b = eval::<Integer>("2").unwrap();
+    /// let (q, m) = a.divmod(b).unwrap();
+    /// assert_eq!(q.to_i64().unwrap(), -4);
+    /// assert_eq!(m.to_i64().unwrap(), 1);
+    ///
+    /// let a = eval::<Integer>("2 ** 100 + 3").unwrap();
+    /// let (q, m) = a.divmod(eval("2 ** 99").unwrap()).unwrap();
+    /// assert_eq!(q.to_i64().unwrap(), 2);
+    /// assert_eq!(m.to_i64().unwrap(), 3);
+    ///
+    /// assert!(a.divmod(eval("0").unwrap()).is_err());
+    /// ```
+    pub fn divmod(self, other: Self) -> Result<(Self, Self), Error> {
+        if other.is_zero() {
+            return Err(Error::new(
+                Ruby::get_with(self).exception_zero_div_error(),
+                "divided by 0",
+            ));
+        }
+        self.binary_operation_visit(
+            &other,
+            |a, b| {
+                let handle = Ruby::get_with(a);
+                let (a, b) = (a.to_i64(), b.to_i64());
+                let (mut div, mut modulo) = (a / b, a % b);
+                if modulo != 0 && (modulo < 0) != (b < 0) {
+                    div -= 1;
+                    modulo += b;
+                }
+                Ok((
+                    handle.integer_from_i64(div),
+                    handle.integer_from_i64(modulo),
+                ))
+            },
+            |a, b| {
+                let ary =
+                    protect(|| unsafe { RArray::from_rb_value_unchecked(rb_big_divmod(a, b)) })?;
+                Ok((ary.entry(0)?, ary.entry(1)?))
+            },
+        )
+    }
+
+    /// Raise `self` to the power `exp`.
+    ///
+    /// Returns `Err` if the result would be too large to represent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, rb_assert, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// let i = eval::<Integer>("3").unwrap();
+    /// assert_eq!(i.pow(4).unwrap().to_i64().unwrap(), 81);
+    /// rb_assert!("i == 3 ** 100", i = i.pow(100).unwrap());
+    /// ```
+    pub fn pow(self, exp: u32) -> Result<Self, Error> {
+        let handle = Ruby::get_with(self);
+        let base = match self.integer_type() {
+            IntegerType::Fixnum(fix) => match fix.to_i64().checked_pow(exp) {
+                Some(n) => return Ok(handle.integer_from_i64(n)),
+                None => unsafe { rb_int2big(fix.to_isize()) },
+            },
+            IntegerType::Bignum(big) => big.as_rb_value(),
+        };
+        let exp = handle.integer_from_u64(u64::from(exp));
+        let res = protect(|| unsafe { Value::new(rb_big_pow(base, exp.as_rb_value())) })?;
+        // Ruby returns Infinity (or raises) when the result is too large
+        Integer::from_value(res)
+            .ok_or_else(|| Error::new(handle.exception_range_error(), "exponent too large"))
+    }
+
+    /// Shift `self` left by `bits`.
+    ///
+    /// Returns `Err` if the result would be too large to represent, or
+    /// memory for it can't be allocated.
+    ///
+    /// See also `Integer`'s [`Shl`] impl.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, rb_assert, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// let i = eval::<Integer>("3").unwrap();
+    /// assert_eq!(i.checked_shl(4).unwrap().to_i64().unwrap(), 48);
+    /// rb_assert!("i == 3 << 100", i = i.checked_shl(100).unwrap());
+    /// ```
+    pub fn checked_shl(self, bits: u32) -> Result<Self, Error> {
+        let handle = Ruby::get_with(self);
+        let a = match self.integer_type() {
+            IntegerType::Fixnum(a) => {
+                let raw_a = a.to_i64();
+                if bits < i64::BITS && (raw_a << bits) >> bits == raw_a {
+                    return Ok(handle.integer_from_i64(raw_a << bits));
+                }
+                unsafe { rb_int2big(a.to_isize()) }
+            }
+            IntegerType::Bignum(a) => a.as_rb_value(),
+        };
+        let bits = handle.integer_from_u64(u64::from(bits));
+        protect(|| unsafe {
+            Integer::from_rb_value_unchecked(rb_big_lshift(a, bits.as_rb_value()))
+        })
+    }
+
+    /// Returns the number of bits required to represent `self`, not
+    /// including the sign bit.
+    ///
+    /// As with Ruby's `Integer#bit_length`, for negative numbers this is the
+    /// bit length of the one's complement of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("0").unwrap().bit_length(), 0);
+    /// assert_eq!(eval::<Integer>("255").unwrap().bit_length(), 8);
+    /// assert_eq!(eval::<Integer>("-256").unwrap().bit_length(), 8);
+    /// assert_eq!(eval::<Integer>("2 ** 100").unwrap().bit_length(), 101);
+    /// assert_eq!(eval::<Integer>("-(2 ** 100)").unwrap().bit_length(), 100);
+    /// ```
+    pub fn bit_length(self) -> usize {
+        match self.integer_type() {
+            IntegerType::Fixnum(fix) => {
+                let n = fix.to_i64();
+                let n = if n < 0 { !n } else { n };
+                (i64::BITS - n.leading_zeros()) as usize
+            }
+            IntegerType::Bignum(big) => unsafe {
+                let mut nlz_bits = 0;
+                let bytes = rb_absint_size(big.as_rb_value(), &mut nlz_bits) as usize;
+                let bits = bytes * 8 - nlz_bits as usize;
+                // the one's complement of -2**n is 2**n - 1, one bit shorter
+                if big.is_negative() && rb_absint_singlebit_p(big.as_rb_value()) != 0 {
+                    bits - 1
+                } else {
+                    bits
+                }
+            },
+        }
+    }
+
+    /// Returns a string representation of `self` in the given `radix`.
+    ///
+    /// Digits above 9 are represented by lowercase letters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range `2..=36`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Integer};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert_eq!(eval::<Integer>("255").unwrap().to_string_radix(16), "ff");
+    /// assert_eq!(eval::<Integer>("-5").unwrap().to_string_radix(2), "-101");
+    /// assert_eq!(
+    ///     eval::<Integer>("2 ** 64").unwrap().to_string_radix(16),
+    ///     "10000000000000000"
+    /// );
+    /// ```
+    pub fn to_string_radix(self, radix: u32) -> String {
+        assert_radix(radix);
+        match self.integer_type() {
+            IntegerType::Fixnum(fix) => {
+                let n = fix.to_i64();
+                let mut abs = n.unsigned_abs();
+                let mut digits = Vec::new();
+                loop {
+                    digits.push(char::from_digit((abs % u64::from(radix)) as u32, radix).unwrap());
+                    abs /= u64::from(radix);
+                    if abs == 0 {
+                        break;
+                    }
+                }
+                if n < 0 {
+                    digits.push('-');
+                }
+                digits.iter().rev().collect()
+            }
+            IntegerType::Bignum(big) => unsafe {
+                RString::from_rb_value_unchecked(rb_big2str(big.as_rb_value(), radix as c_int))
+                    .to_string_lossy()
+                    .into_owned()
+            },
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        match self.norm().integer_type() {
+            IntegerType::Fixnum(fix) => fix.to_i64() == 0,
+            IntegerType::Bignum(_) => false,
+        }
+    }
+
     /// Normalize `self`. If `self` is a `Fixnum`, returns `self`. If `self` is
     /// a `Bignum`, if it is small enough to fit in a `Fixnum`, returns a
     /// `Fixnum` with the same value. Otherwise, returns `self`.
@@ -519,6 +1121,14 @@ impl Integer {
     }
 }
 
+fn assert_radix(radix: u32) {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be in the range 2..=36, got {}",
+        radix
+    );
+}
+
 impl fmt::Display for Integer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", unsafe { self.to_s_infallible() })
@@ -682,6 +1292,10 @@ impl MulAssign for Integer {
 impl Div for Integer {
     type Output = Self;
 
+    /// Returns `self` divided by `other`.
+    ///
+    /// As with Ruby's `Integer#/`, the result is rounded towards negative
+    /// infinity, so `(a / b) * b + a % b == a`.
     fn div(self, other: Self) -> Self {
         self.binary_operation_visit(
             &other,
@@ -691,7 +1305,12 @@ impl Div for Integer {
                 // the only case when division can overflow is when dividing
                 // i64::MIN by -1, but Fixnum can't represent that I64::MIN
                 // so we can safely not use checked_div here
-                Ruby::get_with(a).integer_from_i64(raw_a / raw_b)
+                let mut result = raw_a / raw_b;
+                let rem = raw_a % raw_b;
+                if rem != 0 && (rem < 0) != (raw_b < 0) {
+                    result -= 1;
+                }
+                Ruby::get_with(a).integer_from_i64(result)
             },
             |a, b| {
                 let result = unsafe { rb_big_div(a, b) };
@@ -706,3 +1325,226 @@ impl DivAssign for Integer {
         *self = *self / other;
     }
 }
+
+impl Rem for Integer {
+    type Output = Self;
+
+    /// Returns the modulus of `self` divided by `other`.
+    ///
+    /// As with Ruby's `Integer#%`, the result has the same sign as `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    fn rem(self, other: Self) -> Self {
+        if other.is_zero() {
+            panic!("attempt to calculate the remainder with a divisor of zero");
+        }
+        self.binary_operation_visit(
+            &other,
+            |a, b| {
+                let raw_a = a.to_i64();
+                let raw_b = b.to_i64();
+                let mut result = raw_a % raw_b;
+                if result != 0 && (result < 0) != (raw_b < 0) {
+                    result += raw_b;
+                }
+                Ruby::get_with(a).integer_from_i64(result)
+            },
+            |a, b| {
+                let result = unsafe { rb_big_modulo(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl RemAssign for Integer {
+    fn rem_assign(&mut self, other: Self) {
+        *self = *self % other;
+    }
+}
+
+impl Neg for Integer {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self.integer_type() {
+            // Fixnum can't represent i64::MIN, so negation can't overflow
+            IntegerType::Fixnum(a) => Ruby::get_with(a).integer_from_i64(-a.to_i64()),
+            IntegerType::Bignum(a) => unsafe {
+                Integer::from_rb_value_unchecked(rb_big_minus(rb_int2big(0), a.as_rb_value()))
+            },
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if the result would be too large to represent, or memory for it
+/// can't be allocated. See [`Integer::checked_shl`] for a non-panicking
+/// version.
+impl Shl<u32> for Integer {
+    type Output = Self;
+
+    fn shl(self, bits: u32) -> Self {
+        self.checked_shl(bits).unwrap()
+    }
+}
+
+impl ShlAssign<u32> for Integer {
+    fn shl_assign(&mut self, bits: u32) {
+        *self = *self << bits;
+    }
+}
+
+impl Shr<u32> for Integer {
+    type Output = Self;
+
+    fn shr(self, bits: u32) -> Self {
+        match self.integer_type() {
+            IntegerType::Fixnum(a) => {
+                Ruby::get_with(a).integer_from_i64(a.to_i64() >> bits.min(i64::BITS - 1))
+            }
+            IntegerType::Bignum(a) => {
+                let bits = Ruby::get_with(a).integer_from_u64(u64::from(bits));
+                unsafe {
+                    Integer::from_rb_value_unchecked(rb_big_rshift(
+                        a.as_rb_value(),
+                        bits.as_rb_value(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl ShrAssign<u32> for Integer {
+    fn shr_assign(&mut self, bits: u32) {
+        *self = *self >> bits;
+    }
+}
+
+impl BitAnd for Integer {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() & b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_and(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitAndAssign for Integer {
+    fn bitand_assign(&mut self, other: Self) {
+        *self = *self & other;
+    }
+}
+
+impl BitOr for Integer {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() | b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_or(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitOrAssign for Integer {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = *self | other;
+    }
+}
+
+impl BitXor for Integer {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() ^ b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_xor(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitXorAssign for Integer {
+    fn bitxor_assign(&mut self, other: Self) {
+        *self = *self ^ other;
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl IntoValue for num_bigint::BigInt {
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        let (sign, bytes) = self.to_bytes_le();
+        let mut flags = INTEGER_PACK_LITTLE_ENDIAN;
+        if sign == num_bigint::Sign::Minus {
+            flags |= INTEGER_PACK_NEGATIVE;
+        }
+        unsafe { handle.integer_unpack(bytes.as_ptr() as *const c_void, bytes.len(), 1, flags) }
+            .into_value_with(handle)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+unsafe impl IntoValueFromNative for num_bigint::BigInt {}
+
+#[cfg(feature = "num-bigint")]
+impl IntoValue for num_bigint::BigUint {
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        handle
+            .integer_from_bytes_le(&self.to_bytes_le())
+            .into_value_with(handle)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+unsafe impl IntoValueFromNative for num_bigint::BigUint {}
+
+#[cfg(feature = "num-bigint")]
+impl TryConvert for num_bigint::BigInt {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        let (sign, bytes) = Integer::try_convert(val)?.pack_bytes(INTEGER_PACK_LITTLE_ENDIAN);
+        let sign = match sign {
+            0 => num_bigint::Sign::NoSign,
+            s if s < 0 => num_bigint::Sign::Minus,
+            _ => num_bigint::Sign::Plus,
+        };
+        Ok(Self::from_bytes_le(sign, &bytes))
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+unsafe impl TryConvertOwned for num_bigint::BigInt {}
+
+#[cfg(feature = "num-bigint")]
+impl TryConvert for num_bigint::BigUint {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        let (sign, bytes) = Integer::try_convert(val)?.pack_bytes(INTEGER_PACK_LITTLE_ENDIAN);
+        if sign < 0 {
+            return Err(Error::new(
+                Ruby::get_with(val).exception_range_error(),
+                "can't convert negative integer to unsigned",
+            ));
+        }
+        Ok(Self::from_bytes_le(&bytes))
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+unsafe impl TryConvertOwned for num_bigint::BigUint {}
@@ -0,0 +1,691 @@
+//! Types for working with Ruby IO objects from Rust's [`std::io`].
+
+use std::{
+    cell::RefCell,
+    fmt,
+    io::{self, BufRead as _, Read as _, Write as _},
+};
+
+use rb_sys::{rb_gc_register_address, rb_gc_unregister_address, rb_io_flush, rb_io_write, VALUE};
+
+#[cfg(unix)]
+use crate::error::ErrorType;
+use crate::{
+    class::{Class, RClass},
+    data_type_builder,
+    encoding::Index,
+    error::{protect, Error, OpaqueError},
+    method,
+    module::Module,
+    r_string::RString,
+    scan_args::scan_args,
+    try_convert::TryConvert,
+    typed_data::{DataType, DataTypeFunctions, Obj, TypedData},
+    value::{private::ReprValue as _, Lazy, ReprValue, Value},
+    Ruby,
+};
+
+// matches the default buffer size of `std::io::BufReader`
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+/// An adapter implementing [`Read`](io::Read), [`Write`](io::Write), and
+/// [`BufRead`](io::BufRead) for a Ruby IO object.
+///
+/// Any object responding to `read` and/or `write` can be used, for example
+/// `File`, `StringIO`, or a socket. Reading and writing is done by calling
+/// the object's methods, so Ruby's own IO buffering is respected.
+///
+/// Reads and writes are in bytes, ignoring the object's encoding (like
+/// Ruby's `IO#read(length)`). Writes are passed to the object as binary
+/// `String`s, so any transcoding configured on the IO is still applied.
+///
+/// Errors from Ruby are returned as an [`io::Error`] wrapping a
+/// [`RubyIoError`]. The original [`Error`] can be recovered with
+/// [`RubyIoError::into_error_with`], or by converting the `io::Error` with
+/// [`Ruby::error_from_io_error`].
+///
+/// The [`BufRead`](io::BufRead) implementation reads ahead in to an internal
+/// buffer, data left in this buffer when the `RIo` is dropped will be lost.
+///
+/// Note that `RIo` contains a Ruby value, so must be kept on the stack of a
+/// Ruby thread to prevent it from being Garbage Collected.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{BufRead, Write};
+///
+/// use magnus::{io::RIo, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.require("stringio")?;
+///
+///     let mut io = RIo::new(ruby.eval::<magnus::Value>(r#"StringIO.new("a\nb\n")"#)?)?;
+///     let lines = io.lines().collect::<Result<Vec<_>, _>>().unwrap();
+///     assert_eq!(lines, ["a", "b"]);
+///
+///     let out = ruby.eval::<magnus::Value>("StringIO.new")?;
+///     let mut io = RIo::new(out)?;
+///     write!(io, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+///     io.flush().unwrap();
+///     let s: String = out.funcall("string", ())?;
+///     assert_eq!(s, "1 + 2 = 3");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct RIo {
+    io: Value,
+    readpartial: bool,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl RIo {
+    /// Create a new `RIo` reading from and/or writing to `io`.
+    ///
+    /// Returns an error if `io` responds to neither `read` nor `write`.
+    pub fn new<T>(io: T) -> Result<Self, Error>
+    where
+        T: ReprValue,
+    {
+        let io = io.as_value();
+        if !io.respond_to("read", false)? && !io.respond_to("write", false)? {
+            return Err(Error::new(
+                Ruby::get_with(io).exception_type_error(),
+                format!("no implicit conversion of {} into IO", unsafe {
+                    io.classname()
+                }),
+            ));
+        }
+        Ok(Self {
+            io,
+            readpartial: io.respond_to("readpartial", false)?,
+            buf: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Return the wrapped Ruby object.
+    pub fn as_value(&self) -> Value {
+        self.io
+    }
+
+    /// Read up to `len` bytes from the Ruby object, returning `None` at end
+    /// of file.
+    fn read_ruby(&self, len: usize) -> Result<Option<RString>, Error> {
+        if !self.readpartial {
+            return self.io.funcall("read", (len,));
+        }
+        match self.io.funcall("readpartial", (len,)) {
+            Ok(s) => Ok(Some(s)),
+            Err(e) if e.is_kind_of(Ruby::get_with(self.io).exception_eof_error()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl fmt::Debug for RIo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RIo")
+            .field("io", &self.io)
+            .field("buffered", &(self.buf.len() - self.pos))
+            .finish()
+    }
+}
+
+impl io::Read for RIo {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos < self.buf.len() {
+            let available = &self.buf[self.pos..];
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            self.pos += n;
+            return Ok(n);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.read_ruby(buf.len()).map_err(io_error)? {
+            Some(s) => {
+                let slice = unsafe { s.as_slice() };
+                let n = slice.len().min(buf.len());
+                buf[..n].copy_from_slice(&slice[..n]);
+                // duck-typed IOs may return more than asked for, keep the
+                // excess for the next read
+                self.buf.clear();
+                self.buf.extend_from_slice(&slice[n..]);
+                self.pos = 0;
+                Ok(n)
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+impl io::BufRead for RIo {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            if let Some(s) = self.read_ruby(DEFAULT_BUF_SIZE).map_err(io_error)? {
+                self.buf.extend_from_slice(unsafe { s.as_slice() });
+            }
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
+
+impl io::Write for RIo {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let handle = Ruby::get_with(self.io);
+        let s = handle.str_from_slice(buf);
+        let res =
+            protect(|| unsafe { Value::new(rb_io_write(self.io.as_rb_value(), s.as_rb_value())) })
+                .map_err(io_error)?;
+        // some IO-like objects don't return the number of bytes written, in
+        // which case assume all were. Newline conversion or transcoding can
+        // report more bytes than we passed, which must not exceed `buf.len()`
+        Ok(usize::try_convert(res).unwrap_or(buf.len()).min(buf.len()))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.io.respond_to("flush", false).map_err(io_error)? {
+            return Ok(());
+        }
+        protect(|| unsafe { Value::new(rb_io_flush(self.io.as_rb_value())) }).map_err(io_error)?;
+        Ok(())
+    }
+}
+
+impl TryConvert for RIo {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::new(val)
+    }
+}
+
+/// An error from Ruby, wrapped in an [`io::Error`].
+///
+/// The Ruby exception is protected from garbage collection for as long as
+/// the `RubyIoError` is alive. If it is dropped on a non-Ruby thread the
+/// exception can't be unregistered, and will never be garbage collected.
+///
+/// See [`RIo`].
+pub struct RubyIoError {
+    pub(crate) error: OpaqueError,
+    // keeps the exception alive for as long as the `io::Error` is
+    _root: Option<GcRoot>,
+    message: String,
+}
+
+impl RubyIoError {
+    /// Convert a `RubyIoError` back in to the original [`Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use magnus::{
+    ///     io::{RIo, RubyIoError},
+    ///     Error, Ruby,
+    /// };
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let mut io = RIo::new(ruby.eval::<magnus::Value>("r, _w = IO.pipe; r.close; r")?)?;
+    ///     let err = io.read(&mut [0; 8]).unwrap_err();
+    ///     let err = err
+    ///         .into_inner()
+    ///         .unwrap()
+    ///         .downcast::<RubyIoError>()
+    ///         .unwrap();
+    ///     let err = RubyIoError::into_error_with(*err, ruby);
+    ///     assert!(err.is_kind_of(ruby.exception_io_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn into_error_with(this: Self, handle: &Ruby) -> Error {
+        OpaqueError::into_error_with(this.error, handle)
+    }
+}
+
+impl fmt::Display for RubyIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl fmt::Debug for RubyIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RubyIoError")
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl std::error::Error for RubyIoError {}
+
+/// Registers a value with Ruby's GC so it won't be collected while this is
+/// alive.
+///
+/// Unlike [`BoxValue`](crate::value::BoxValue) this is `Send`, so can be
+/// stored in an `io::Error`.
+struct GcRoot(*mut VALUE);
+
+// VALUE is just an integer, and the registered address is only touched with
+// the GVL held
+unsafe impl Send for GcRoot {}
+unsafe impl Sync for GcRoot {}
+
+impl GcRoot {
+    fn new(val: Value) -> Self {
+        let ptr = Box::into_raw(Box::new(val.as_rb_value()));
+        unsafe { rb_gc_register_address(ptr) };
+        Self(ptr)
+    }
+}
+
+impl Drop for GcRoot {
+    fn drop(&mut self) {
+        // unregistering needs the GVL. If dropped on a non-Ruby thread the
+        // best we can do is leak the registration, along with the address it
+        // points to
+        let _ = Ruby::with_gvl(|_| unsafe {
+            rb_gc_unregister_address(self.0);
+            drop(Box::from_raw(self.0));
+        });
+    }
+}
+
+/// Convert a Ruby error to an `io::Error`, with an [`io::ErrorKind`] based on
+/// the exception class.
+fn io_error(err: Error) -> io::Error {
+    let kind = io_error_kind(&err);
+    let message = err.to_string();
+    let root = err.value().map(GcRoot::new);
+    io::Error::new(
+        kind,
+        RubyIoError {
+            error: err.into(),
+            _root: root,
+            message,
+        },
+    )
+}
+
+fn io_error_kind(err: &Error) -> io::ErrorKind {
+    let handle = unsafe { Ruby::get_unchecked() };
+    if err.is_kind_of(handle.exception_eof_error()) {
+        return io::ErrorKind::UnexpectedEof;
+    }
+    // Ruby's errno values only match the OS's on unix
+    #[cfg(unix)]
+    if let ErrorType::Exception(e) = err.error_type() {
+        if e.is_kind_of(handle.exception_system_call_error()) {
+            if let Ok(errno) = e.funcall::<_, _, i32>("errno", ()) {
+                return io::Error::from_raw_os_error(errno).kind();
+            }
+        }
+    }
+    io::ErrorKind::Other
+}
+
+trait ReadWrite: io::Read + io::Write {}
+
+impl<T> ReadWrite for T where T: io::Read + io::Write {}
+
+enum Stream {
+    Reader(io::BufReader<Box<dyn io::Read + Send>>),
+    Writer(Box<dyn io::Write + Send>),
+    Duplex(io::BufReader<Box<dyn ReadWrite + Send>>),
+    Closed,
+}
+
+/// A Rust [`Read`](io::Read) and/or [`Write`](io::Write) stream, exposed to
+/// Ruby as an IO-like object.
+///
+/// When converted to Ruby this is wrapped in an instance of
+/// `Magnus::IoAdapter`, which implements the common duck-typed IO methods:
+/// `read`, `readpartial`, `gets`, `each_line`/`each`, `write`, `<<`, `flush`,
+/// `close`, `closed?`, and `eof?`. It also includes `Enumerable`.
+///
+/// `read` with a length and `readpartial` return `ASCII-8BIT` strings, as
+/// with Ruby's `IO`. Other methods return strings with the encoding set with
+/// [`with_encoding`](RubyIoAdapter::with_encoding), defaulting to
+/// `ASCII-8BIT`.
+///
+/// Reads and writes are performed with the GVL held, so will block other
+/// Ruby threads.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, io::RubyIoAdapter, rb_assert, Error, Ruby};
+///
+/// fn numbers(ruby: &Ruby) -> RubyIoAdapter {
+///     let data = (1..=3).map(|i| format!("{}\n", i)).collect::<String>();
+///     RubyIoAdapter::reader(std::io::Cursor::new(data)).with_encoding(ruby.utf8_encindex())
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("numbers", function!(numbers, 0));
+///
+///     rb_assert!(ruby, "numbers.each_line.map(&:to_i) == [1, 2, 3]");
+///     rb_assert!(ruby, "numbers.gets.encoding == Encoding::UTF_8");
+///     rb_assert!(ruby, "numbers.read(3) == \"1\\n2\"");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct RubyIoAdapter {
+    stream: RefCell<Stream>,
+    encoding: Option<Index>,
+}
+
+impl RubyIoAdapter {
+    /// Create a new `RubyIoAdapter` reading from `reader`.
+    pub fn reader<R>(reader: R) -> Self
+    where
+        R: io::Read + Send + 'static,
+    {
+        Self::new(Stream::Reader(io::BufReader::new(Box::new(reader))))
+    }
+
+    /// Create a new `RubyIoAdapter` writing to `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{
+    ///     io::Write,
+    ///     sync::{Arc, Mutex},
+    /// };
+    ///
+    /// use magnus::{io::RubyIoAdapter, rb_assert, Error, Ruby};
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct Shared(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl Write for Shared {
+    ///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    ///         self.0.lock().unwrap().write(buf)
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> std::io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = Shared::default();
+    ///     let io = ruby.obj_wrap(RubyIoAdapter::writer(buf.clone()));
+    ///     rb_assert!(ruby, r#"io.write("hello", ", ") == 7"#, io);
+    ///     rb_assert!(ruby, r#"(io << "world").closed? == false"#, io);
+    ///
+    ///     assert_eq!(*buf.0.lock().unwrap(), b"hello, world");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn writer<W>(writer: W) -> Self
+    where
+        W: io::Write + Send + 'static,
+    {
+        Self::new(Stream::Writer(Box::new(writer)))
+    }
+
+    /// Create a new `RubyIoAdapter` both reading from and writing to
+    /// `stream`, such as a [`TcpStream`](std::net::TcpStream).
+    pub fn duplex<T>(stream: T) -> Self
+    where
+        T: io::Read + io::Write + Send + 'static,
+    {
+        Self::new(Stream::Duplex(io::BufReader::new(Box::new(stream))))
+    }
+
+    fn new(stream: Stream) -> Self {
+        Self {
+            stream: RefCell::new(stream),
+            encoding: None,
+        }
+    }
+
+    /// Set the encoding of strings returned by `read` (without a length),
+    /// `gets`, and `each_line`.
+    pub fn with_encoding<T>(mut self, enc: T) -> Self
+    where
+        T: Into<Index>,
+    {
+        self.encoding = Some(enc.into());
+        self
+    }
+
+    fn reader_mut<'a>(
+        ruby: &Ruby,
+        stream: &'a mut Stream,
+    ) -> Result<&'a mut dyn io::BufRead, Error> {
+        match stream {
+            Stream::Reader(r) => Ok(r),
+            Stream::Duplex(r) => Ok(r),
+            Stream::Writer(_) => Err(Error::new(
+                ruby.exception_io_error(),
+                "not opened for reading",
+            )),
+            Stream::Closed => Err(Error::new(ruby.exception_io_error(), "closed stream")),
+        }
+    }
+
+    fn writer_mut<'a>(ruby: &Ruby, stream: &'a mut Stream) -> Result<&'a mut dyn io::Write, Error> {
+        match stream {
+            Stream::Writer(w) => Ok(w),
+            Stream::Duplex(rw) => Ok(rw.get_mut()),
+            Stream::Reader(_) => Err(Error::new(
+                ruby.exception_io_error(),
+                "not opened for writing",
+            )),
+            Stream::Closed => Err(Error::new(ruby.exception_io_error(), "closed stream")),
+        }
+    }
+
+    fn str_new(&self, ruby: &Ruby, bytes: &[u8]) -> RString {
+        match self.encoding {
+            Some(enc) => ruby.enc_str_new(bytes, enc),
+            None => ruby.str_from_slice(bytes),
+        }
+    }
+
+    // The methods below take care to not call in to Ruby while `stream` is
+    // borrowed, as Ruby code (e.g. a `to_s` method, or an exception's
+    // `initialize`) could call back in to the adapter.
+
+    fn read(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<Option<RString>, Error> {
+        let args = scan_args::<(), (Option<Option<usize>>,), (), (), (), ()>(args)?;
+        let (len,) = args.optional;
+        let len = len.flatten();
+        let mut buf = Vec::new();
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            let reader = Self::reader_mut(ruby, &mut stream)?;
+            match len {
+                None => reader.read_to_end(&mut buf),
+                Some(len) => reader.take(len as u64).read_to_end(&mut buf),
+            }
+        };
+        res.map_err(|e| ruby.error_from_io_error(&e))?;
+        match len {
+            None => Ok(Some(rb_self.str_new(ruby, &buf))),
+            Some(0) => Ok(Some(ruby.str_new(""))),
+            Some(_) => Ok((!buf.is_empty()).then(|| ruby.str_from_slice(&buf))),
+        }
+    }
+
+    fn readpartial(ruby: &Ruby, rb_self: &Self, maxlen: usize) -> Result<RString, Error> {
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            let reader = Self::reader_mut(ruby, &mut stream)?;
+            if maxlen == 0 {
+                return Ok(ruby.str_new(""));
+            }
+            let buf = reader
+                .fill_buf()
+                .map(|available| available[..available.len().min(maxlen)].to_vec());
+            if let Ok(buf) = &buf {
+                reader.consume(buf.len());
+            }
+            buf
+        };
+        let buf = res.map_err(|e| ruby.error_from_io_error(&e))?;
+        if buf.is_empty() {
+            return Err(Error::new(
+                ruby.exception_eof_error(),
+                "end of file reached",
+            ));
+        }
+        Ok(ruby.str_from_slice(&buf))
+    }
+
+    fn gets(ruby: &Ruby, rb_self: &Self) -> Result<Option<RString>, Error> {
+        let mut buf = Vec::new();
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            Self::reader_mut(ruby, &mut stream)?.read_until(b'\n', &mut buf)
+        };
+        res.map_err(|e| ruby.error_from_io_error(&e))?;
+        Ok((!buf.is_empty()).then(|| rb_self.str_new(ruby, &buf)))
+    }
+
+    fn each_line(ruby: &Ruby, rb_self: Obj<Self>) -> Result<Value, Error> {
+        if !ruby.block_given() {
+            return Ok(rb_self.enumeratorize("each_line", ()).as_value());
+        }
+        // the borrow must be released before yielding, as the block may
+        // call methods on `rb_self`
+        while let Some(line) = Self::gets(ruby, &rb_self)? {
+            let _: Value = ruby.yield_value(line)?;
+        }
+        Ok(rb_self.as_value())
+    }
+
+    fn write(ruby: &Ruby, rb_self: &Self, args: &[Value]) -> Result<usize, Error> {
+        // check the stream is writable before converting any arguments
+        Self::writer_mut(ruby, &mut rb_self.stream.borrow_mut())?;
+        let mut written = 0;
+        for arg in args {
+            let s = arg.to_r_string()?;
+            let bytes = unsafe { s.as_slice() };
+            let res = {
+                let mut stream = rb_self.stream.borrow_mut();
+                Self::writer_mut(ruby, &mut stream)?.write_all(bytes)
+            };
+            res.map_err(|e| ruby.error_from_io_error(&e))?;
+            written += bytes.len();
+        }
+        Ok(written)
+    }
+
+    fn push(ruby: &Ruby, rb_self: Obj<Self>, val: Value) -> Result<Obj<Self>, Error> {
+        Self::write(ruby, &rb_self, &[val])?;
+        Ok(rb_self)
+    }
+
+    fn flush(ruby: &Ruby, rb_self: Obj<Self>) -> Result<Obj<Self>, Error> {
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            Self::writer_mut(ruby, &mut stream)?.flush()
+        };
+        res.map_err(|e| ruby.error_from_io_error(&e))?;
+        Ok(rb_self)
+    }
+
+    fn close(ruby: &Ruby, rb_self: &Self) -> Result<(), Error> {
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            let res = match Self::writer_mut(ruby, &mut stream) {
+                Ok(writer) => writer.flush(),
+                Err(_) => Ok(()),
+            };
+            *stream = Stream::Closed;
+            res
+        };
+        res.map_err(|e| ruby.error_from_io_error(&e))
+    }
+
+    fn is_closed(&self) -> bool {
+        matches!(*self.stream.borrow(), Stream::Closed)
+    }
+
+    fn is_eof(ruby: &Ruby, rb_self: &Self) -> Result<bool, Error> {
+        let res = {
+            let mut stream = rb_self.stream.borrow_mut();
+            Self::reader_mut(ruby, &mut stream)?
+                .fill_buf()
+                .map(|buf| buf.is_empty())
+        };
+        res.map_err(|e| ruby.error_from_io_error(&e))
+    }
+}
+
+impl DataTypeFunctions for RubyIoAdapter {}
+
+unsafe impl TypedData for RubyIoAdapter {
+    fn class(ruby: &Ruby) -> RClass {
+        static CLASS: Lazy<RClass> = Lazy::new(|ruby| {
+            let class = ruby
+                .define_module("Magnus")
+                .and_then(|module| module.define_class("IoAdapter", ruby.class_object()))
+                .unwrap();
+            class.undef_default_alloc_func();
+            class.include_module(ruby.module_enumerable()).unwrap();
+            class
+                .define_method("read", method!(RubyIoAdapter::read, -1))
+                .unwrap();
+            class
+                .define_method("readpartial", method!(RubyIoAdapter::readpartial, 1))
+                .unwrap();
+            class
+                .define_method("gets", method!(RubyIoAdapter::gets, 0))
+                .unwrap();
+            class
+                .define_method("each_line", method!(RubyIoAdapter::each_line, 0))
+                .unwrap();
+            class.define_alias("each", "each_line").unwrap();
+            class
+                .define_method("write", method!(RubyIoAdapter::write, -1))
+                .unwrap();
+            class
+                .define_method("<<", method!(RubyIoAdapter::push, 1))
+                .unwrap();
+            class
+                .define_method("flush", method!(RubyIoAdapter::flush, 0))
+                .unwrap();
+            class
+                .define_method("close", method!(RubyIoAdapter::close, 0))
+                .unwrap();
+            class
+                .define_method("closed?", method!(RubyIoAdapter::is_closed, 0))
+                .unwrap();
+            class
+                .define_method("eof?", method!(RubyIoAdapter::is_eof, 0))
+                .unwrap();
+            class.define_alias("eof", "eof?").unwrap();
+            class
+        });
+        ruby.get_inner(&CLASS)
+    }
+
+    fn data_type() -> &'static DataType {
+        static DATA_TYPE: DataType = data_type_builder!(RubyIoAdapter, "Magnus::IoAdapter")
+            .free_immediately()
+            .build();
+        &DATA_TYPE
+    }
+}
@@ -218,8 +218,8 @@
 //!
 //! ## `rb_a`-`rb_arx`
 // * `rb_absint_numwords`:
-// * `rb_absint_singlebit_p`:
-// * `rb_absint_size`:
+//! * `rb_absint_singlebit_p`: Called internally by Magnus when required.
+//! * `rb_absint_size`: Called internally by Magnus when required.
 // * `rb_add_event_hook`:
 // * `rb_add_event_hook2`:
 //! * `rb_alias`: [`Module::define_alias`].
@@ -306,35 +306,35 @@
 // * `rb_big2int`:
 // * `rb_big2ll`:
 // * `rb_big2long`:
-// * `rb_big2str`:
+//! * `rb_big2str`: [`Integer::to_string_radix`].
 // * `rb_big2uint`:
 // * `rb_big2ull`:
 // * `rb_big2ulong`:
 // * `rb_bigzero_p`:
 // * `rb_big_2comp`:
-// * `rb_big_and`:
+//! * `rb_big_and`: See [`Integer`]'s [`BitAnd`](std::ops::BitAnd) impl.
 // * `rb_big_clone`:
-// * `rb_big_cmp`:
-// * `rb_big_div`:
-// * `rb_big_divmod`:
-// * `rb_big_eq`:
+//! * `rb_big_cmp`: See [`Integer`]'s [`PartialOrd`] impl.
+//! * `rb_big_div`: See [`Integer`]'s [`Div`](std::ops::Div) impl.
+//! * `rb_big_divmod`: [`Integer::divmod`].
+//! * `rb_big_eq`: See [`Integer`]'s [`PartialEq`] impl.
 // * `rb_big_eql`:
 // * `rb_big_idiv`:
-// * `rb_big_lshift`:
-// * `rb_big_minus`:
-// * `rb_big_modulo`:
-// * `rb_big_mul`:
+//! * `rb_big_lshift`: [`Integer::checked_shl`].
+//! * `rb_big_minus`: See [`Integer`]'s [`Sub`](std::ops::Sub) impl.
+//! * `rb_big_modulo`: See [`Integer`]'s [`Rem`](std::ops::Rem) impl.
+//! * `rb_big_mul`: See [`Integer`]'s [`Mul`](std::ops::Mul) impl.
 // * `rb_big_new`:
 //! * `rb_big_norm`: [`Integer::norm`].
-// * `rb_big_or`:
+//! * `rb_big_or`: See [`Integer`]'s [`BitOr`](std::ops::BitOr) impl.
 // * `rb_big_pack`:
-// * `rb_big_plus`:
-// * `rb_big_pow`:
+//! * `rb_big_plus`: See [`Integer`]'s [`Add`](std::ops::Add) impl.
+//! * `rb_big_pow`: [`Integer::pow`].
 // * `rb_big_resize`:
-// * `rb_big_rshift`:
+//! * `rb_big_rshift`: See [`Integer`]'s [`Shr`](std::ops::Shr) impl.
 // * `rb_big_sign`:
 // * `rb_big_unpack`:
-// * `rb_big_xor`:
+//! * `rb_big_xor`: See [`Integer`]'s [`BitXor`](std::ops::BitXor) impl.
 //! * `rb_block_call`: See [`Value::block_call`].
 //! * `rb_block_call_kw`: [`Value::block_call`].
 //! * `rb_block_given_p`: [`block::block_given`].
@@ -348,8 +348,8 @@
 //!
 //! * `rb_call_super`: See [`call_super`].
 //! * `rb_call_super_kw`: [`call_super`].
-// * `rb_catch`:
-// * `rb_catch_obj`:
+//! * `rb_catch`: See [`Ruby::catch`].
+//! * `rb_catch_obj`: [`Ruby::catch`].
 // * `rb_category_compile_warn`:
 // * `rb_category_warn`:
 // * `rb_category_warning`:
@@ -675,8 +675,8 @@
 // * `rb_external_str_new_cstr`:
 // * `rb_external_str_new_with_enc`:
 // * `rb_extract_keywords`:
-// * `RB_EXT_RACTOR_SAFE`:
-// * `rb_ext_ractor_safe`:
+//! * `RB_EXT_RACTOR_SAFE`: See [`Ruby::ext_ractor_safe`].
+//! * `rb_ext_ractor_safe`: [`Ruby::ext_ractor_safe`].
 //!
 //! ## `rb_f`
 // * `rb_fatal`:
@@ -703,29 +703,29 @@
 //! * `rb_fiber_resume`: See [`Fiber::resume`].
 //! * `rb_fiber_resume_kw`: [`Fiber::resume`].
 // * `rb_fiber_scheduler_address_resolve`:
-// * `rb_fiber_scheduler_block`:
+//! * `rb_fiber_scheduler_block`: [`Scheduler::block`](fiber::Scheduler::block).
 // * `rb_fiber_scheduler_close`:
-// * `rb_fiber_scheduler_current`:
+//! * `rb_fiber_scheduler_current`: [`Ruby::fiber_scheduler_current`].
 // * `rb_fiber_scheduler_current_for_thread`:
-// * `rb_fiber_scheduler_get`:
+//! * `rb_fiber_scheduler_get`: [`Ruby::fiber_scheduler_get`].
 // * `rb_fiber_scheduler_io_close`:
 // * `rb_fiber_scheduler_io_pread`:
 // * `rb_fiber_scheduler_io_pwrite`:
-// * `rb_fiber_scheduler_io_read`:
-// * `rb_fiber_scheduler_io_read_memory`:
+//! * `rb_fiber_scheduler_io_read`: See [`Scheduler::io_read`](fiber::Scheduler::io_read).
+//! * `rb_fiber_scheduler_io_read_memory`: [`Scheduler::io_read`](fiber::Scheduler::io_read).
 // * `rb_fiber_scheduler_io_result`:
 // * `rb_fiber_scheduler_io_result_apply`:
-// * `rb_fiber_scheduler_io_wait`:
-// * `rb_fiber_scheduler_io_wait_readable`:
-// * `rb_fiber_scheduler_io_wait_writable`:
-// * `rb_fiber_scheduler_io_write`:
-// * `rb_fiber_scheduler_io_write_memory`:
-// * `rb_fiber_scheduler_kernel_sleep`:
+//! * `rb_fiber_scheduler_io_wait`: [`Scheduler::io_wait`](fiber::Scheduler::io_wait).
+//! * `rb_fiber_scheduler_io_wait_readable`: [`Scheduler::io_wait_readable`](fiber::Scheduler::io_wait_readable).
+//! * `rb_fiber_scheduler_io_wait_writable`: [`Scheduler::io_wait_writable`](fiber::Scheduler::io_wait_writable).
+//! * `rb_fiber_scheduler_io_write`: See [`Scheduler::io_write`](fiber::Scheduler::io_write).
+//! * `rb_fiber_scheduler_io_write_memory`: [`Scheduler::io_write`](fiber::Scheduler::io_write).
+//! * `rb_fiber_scheduler_kernel_sleep`: [`Scheduler::kernel_sleep`](fiber::Scheduler::kernel_sleep).
 // * `rb_fiber_scheduler_kernel_sleepv`:
 // * `rb_fiber_scheduler_make_timeout`:
 // * `rb_fiber_scheduler_process_wait`:
 // * `rb_fiber_scheduler_set`:
-// * `rb_fiber_scheduler_unblock`:
+//! * `rb_fiber_scheduler_unblock`: [`Scheduler::unblock`](fiber::Scheduler::unblock).
 //! * `rb_fiber_transfer`: See [`Fiber::transfer`].
 //! * `rb_fiber_transfer_kw`: [`Fiber::transfer`].
 //! * `rb_fiber_yield`: See [`Ruby::fiber_yield`].
@@ -834,8 +834,9 @@
 //! * `rb_gc_stat`: [`gc::stat`] or [`gc::all_stats`].
 //! * `rb_gc_unregister_address`: [`gc::unregister_address`].
 // * `rb_gc_update_tbl_refs`:
-// * `rb_gc_writebarrier`:
-// * `rb_gc_writebarrier_unprotect`:
+//! * `rb_gc_writebarrier`: [`Obj::write_barrier`](typed_data::Obj::write_barrier).
+//! * `rb_gc_writebarrier_unprotect`:
+//!   [`Obj::write_barrier_unprotect`](typed_data::Obj::write_barrier_unprotect).
 // * `rb_generic_ivar_table`:
 // * `rb_genrand_int32`:
 // * `rb_genrand_real`:
@@ -901,9 +902,12 @@
 // * `RB_INT2NUM`:
 // * `rb_int2num_inline`:
 // * `rb_Integer`:
-// * `rb_integer_pack`:
+//! * `rb_integer_pack`: See [`Integer::to_bytes_le`], [`Integer::to_bytes_be`],
+//!   [`Integer::to_i128`], and [`Integer::to_u128`].
 // * `rb_integer_type_p`:
-// * `rb_integer_unpack`:
+//! * `rb_integer_unpack`: See [`Ruby::integer_from_bytes_le`],
+//!   [`Ruby::integer_from_bytes_be`], [`Ruby::integer_from_i128`], and
+//!   [`Ruby::integer_from_u128`].
 //! * `rb_intern`: [`std::convert::From`].
 //! * `rb_intern2`: [`std::convert::From`].
 //! * `rb_intern3`: [`std::convert::From`].
@@ -935,11 +939,11 @@
 // * `rb_io_extract_encoding_option`:
 // * `rb_io_extract_modeenc`:
 // * `rb_io_fdopen`:
-// * `rb_io_flush`:
+//! * `rb_io_flush`: See [`io::RIo`]'s [`Write`](std::io::Write) impl.
 // * `rb_io_fptr_finalize`:
 // * `rb_io_getbyte`:
 // * `rb_io_gets`:
-// * `rb_io_get_io`:
+//! * `rb_io_get_io`: See [`TryConvert`] for [`BorrowedFd`](std::os::unix::io::BorrowedFd).
 // * `rb_io_get_write_io`:
 // * `rb_io_make_open_file`:
 // * `rb_io_maybe_wait`:
@@ -962,7 +966,7 @@
 // * `rb_io_ungetbyte`:
 // * `rb_io_ungetc`:
 // * `rb_io_wait`:
-// * `rb_io_write`:
+//! * `rb_io_write`: See [`io::RIo`]'s [`Write`](std::io::Write) impl.
 //!
 //! ## `rb_is`-`rb_iz`
 // * `rb_isalnum`:
@@ -1035,8 +1039,8 @@
 // * `rb_mark_tbl`:
 // * `rb_mark_tbl_no_pin`:
 // * `rb_marshal_define_compat`:
-// * `rb_marshal_dump`:
-// * `rb_marshal_load`:
+//! * `rb_marshal_dump`: [`Ruby::marshal_dump`].
+//! * `rb_marshal_load`: [`Ruby::marshal_load`].
 // * `rb_match_busy`:
 // * `rb_memcicmp`:
 // * `rb_memerror`:
@@ -1238,16 +1242,16 @@
 // * `rb_provided`:
 //!
 //! ## `rb_r`
-// * `rb_ractor_local_storage_ptr`:
-// * `rb_ractor_local_storage_ptr_newkey`:
-// * `rb_ractor_local_storage_ptr_set`:
+//! * `rb_ractor_local_storage_ptr`: See [`ractor::RactorLocal`].
+//! * `rb_ractor_local_storage_ptr_newkey`: See [`ractor::RactorLocal`].
+//! * `rb_ractor_local_storage_ptr_set`: See [`ractor::RactorLocal`].
 // * `rb_ractor_local_storage_value`:
 // * `rb_ractor_local_storage_value_lookup`:
 // * `rb_ractor_local_storage_value_newkey`:
 // * `rb_ractor_local_storage_value_set`:
-// * `rb_ractor_make_shareable`:
-// * `rb_ractor_make_shareable_copy`:
-// * `rb_ractor_shareable_p`:
+//! * `rb_ractor_make_shareable`: [`Ruby::make_shareable`].
+//! * `rb_ractor_make_shareable_copy`: [`Ruby::make_shareable_copy`].
+//! * `rb_ractor_shareable_p`: [`Ruby::is_shareable`].
 // * `rb_ractor_stderr`:
 // * `rb_ractor_stderr_set`:
 // * `rb_ractor_stdin`:
@@ -1439,7 +1443,7 @@
 // * `rb_str_tmp_new`:
 // * `rb_str_to_dbl`:
 //! * `rb_str_to_interned_str`: [`RString::to_interned_str`].
-// * `rb_str_to_inum`:
+//! * `rb_str_to_inum`: [`Ruby::integer_from_str_radix`].
 //! * `rb_str_to_str`: [`TryConvert`] or [`Value::try_convert`].
 // * `rb_str_unlocktmp`:
 //! * `rb_str_update`: [`RString::update`].
@@ -1496,8 +1500,8 @@
 // * `rb_sym_to_s`:
 // * `rb_syserr_fail`:
 // * `rb_syserr_fail_str`:
-// * `rb_syserr_new`:
-// * `rb_syserr_new_str`:
+//! * `rb_syserr_new`: See [`Ruby::syserr_new`].
+//! * `rb_syserr_new_str`: [`Ruby::syserr_new`].
 // * `rb_syswait`:
 // * `rb_sys_fail`:
 // * `rb_sys_fail_str`:
@@ -1511,9 +1515,9 @@
 //! * `rb_thread_alone`: [`Ruby::thread_alone`].
 // * `rb_thread_atfork`:
 // * `rb_thread_atfork_before_exec`:
-// * `rb_thread_call_without_gvl`:
+//! * `rb_thread_call_without_gvl`: [`Ruby::without_gvl`].
 // * `rb_thread_call_without_gvl2`:
-// * `rb_thread_call_with_gvl`:
+//! * `rb_thread_call_with_gvl`: [`Ruby::with_gvl`].
 //! * `rb_thread_check_ints`: [`Ruby::thread_check_ints`].
 //! * `rb_thread_create`: [`Ruby::thread_create`] & [`Ruby::thread_create_from_fn`].
 //! * `rb_thread_current`: [`Ruby::thread_current`].
@@ -1537,8 +1541,8 @@
 //! * `rb_thread_wait_for`: [`Ruby::thread_sleep`].
 //! * `rb_thread_wakeup`: [`Thread::wakeup`].
 //! * `rb_thread_wakeup_alive`: [`Thread::wakeup_alive`].
-// * `rb_throw`:
-// * `rb_throw_obj`:
+//! * `rb_throw`: See [`Ruby::throw`].
+//! * `rb_throw_obj`: [`Ruby::throw`].
 // * `rb_timespec_now`:
 // * `rb_time_interval`:
 // * `rb_time_nano_new`:
@@ -1802,6 +1806,7 @@ pub mod encoding;
 mod enumerator;
 pub mod error;
 pub mod exception;
+pub mod executor;
 #[cfg(any(ruby_gte_3_1, docsrs))]
 #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_1)))]
 pub mod fiber;
@@ -1809,6 +1814,8 @@ mod float;
 pub mod gc;
 mod integer;
 mod into_value;
+pub mod io;
+mod marshal;
 pub mod method;
 pub mod module;
 mod mutex;
@@ -1823,6 +1830,7 @@ pub mod prelude {
         value::ReprValue as _,
     };
 }
+pub mod queue;
 pub mod r_array;
 mod r_bignum;
 mod r_complex;
@@ -1836,11 +1844,15 @@ pub mod r_regexp;
 pub mod r_string;
 pub mod r_struct;
 mod r_typed_data;
+pub mod ractor;
 mod range;
 #[cfg(feature = "rb-sys")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rb-sys")))]
 pub mod rb_sys;
 pub mod scan_args;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
 pub mod symbol;
 mod thread;
 mod time;
@@ -1855,7 +1867,9 @@ use ::rb_sys::{
     rb_define_global_const, rb_define_global_function, rb_define_module, rb_define_variable,
     rb_errinfo, rb_eval_string_protect, rb_require_string, rb_set_errinfo, VALUE,
 };
-pub use magnus_macros::{init, wrap, DataTypeFunctions, TypedData};
+pub use magnus_macros::{
+    init, methods, wrap, DataTypeFunctions, IntoRubyError, IntoValue, TryConvert, TypedData,
+};
 
 #[cfg(any(ruby_gte_3_1, docsrs))]
 #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_1)))]
@@ -1866,15 +1880,16 @@ pub use crate::{
     api::Ruby,
     class::{Class, RClass},
     enumerator::Enumerator,
-    error::Error,
+    error::{Error, IntoRubyError},
     exception::{Exception, ExceptionClass},
     float::Float,
     integer::Integer,
     into_value::{ArgList, IntoValue, IntoValueFromNative, KwArgs, RArrayArgList},
     module::{Attr, Module, RModule},
-    mutex::Mutex,
+    mutex::{ConditionVariable, Mutex, MutexGuard},
     numeric::Numeric,
     object::Object,
+    queue::{Queue, SizedQueue},
     r_array::RArray,
     r_bignum::RBignum,
     r_complex::RComplex,
@@ -1890,7 +1905,7 @@ pub use crate::{
     r_typed_data::RTypedData,
     range::Range,
     symbol::Symbol,
-    thread::Thread,
+    thread::{CancellationToken, Thread, ThreadStatus},
     time::Time,
     try_convert::TryConvert,
     typed_data::{DataType, DataTypeFunctions, TypedData},
@@ -0,0 +1,83 @@
+use rb_sys::{rb_marshal_dump, rb_marshal_load};
+
+use crate::{
+    api::Ruby,
+    error::{protect, Error},
+    into_value::IntoValue,
+    r_string::RString,
+    try_convert::TryConvert,
+    value::{private::ReprValue as _, Value},
+};
+
+/// # Marshal
+///
+/// Functions for serialising Ruby objects with Ruby's `Marshal`.
+///
+/// See also [`typed_data::Marshal`](crate::typed_data::Marshal) to support
+/// `Marshal` for wrapped Rust types.
+impl Ruby {
+    /// Serialise `val` to a String with Ruby's `Marshal`.
+    ///
+    /// Equivalent to Ruby's `Marshal.dump(val)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let dumped = ruby.marshal_dump((1, "two", 3.0))?;
+    ///     let (a, b, c): (i64, String, f64) = ruby.marshal_load(dumped)?;
+    ///     assert_eq!(a, 1);
+    ///     assert_eq!(b, "two");
+    ///     assert_eq!(c, 3.0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn marshal_dump<T>(&self, val: T) -> Result<RString, Error>
+    where
+        T: IntoValue,
+    {
+        let val = self.into_value(val);
+        protect(|| unsafe {
+            RString::from_rb_value_unchecked(rb_marshal_dump(
+                val.as_rb_value(),
+                self.qnil().as_rb_value(),
+            ))
+        })
+    }
+
+    /// Deserialise a Ruby object with Ruby's `Marshal`, converting it to `U`.
+    ///
+    /// `source` may be a String, or an IO-like object responding to
+    /// `getbyte` and `read`.
+    ///
+    /// Equivalent to Ruby's `Marshal.load(source)`. As with Ruby's
+    /// `Marshal.load`, this should never be used with untrusted data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, RString, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let dumped: RString = ruby.eval("Marshal.dump({a: 1})")?;
+    ///     let hash: RHash = ruby.marshal_load(dumped)?;
+    ///     assert_eq!(hash.fetch::<_, i64>(ruby.to_symbol("a"))?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn marshal_load<T, U>(&self, source: T) -> Result<U, Error>
+    where
+        T: IntoValue,
+        U: TryConvert,
+    {
+        let source = self.into_value(source);
+        protect(|| unsafe { Value::new(rb_marshal_load(source.as_rb_value())) })
+            .and_then(TryConvert::try_convert)
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
         do_yield_iter, do_yield_splat_iter, do_yield_values_iter, Proc, Yield, YieldSplat,
         YieldValues,
     },
-    error::{raise, Error},
+    error::{raise, Error, IntoRubyError},
     into_value::{ArgList, IntoValue},
     r_array::RArray,
     try_convert::TryConvert,
@@ -74,12 +74,14 @@ mod private {
         fn into_return_value(self) -> Result<Value, Error>;
     }
 
-    impl<T> ReturnValue for Result<T, Error>
+    impl<T, E> ReturnValue for Result<T, E>
     where
         T: IntoValue,
+        E: IntoRubyError,
     {
         fn into_return_value(self) -> Result<Value, Error> {
             self.map(|val| unsafe { val.into_value_unchecked() })
+                .map_err(|e| e.into_ruby_error(&unsafe { Ruby::get_unchecked() }))
         }
     }
 
@@ -88,7 +90,7 @@ mod private {
         T: IntoValue,
     {
         fn into_return_value(self) -> Result<Value, Error> {
-            Ok(self).into_return_value()
+            Ok::<T, Error>(self).into_return_value()
         }
     }
 
@@ -108,13 +110,15 @@ mod private {
         }
     }
 
-    impl<I, T> ReturnValue for Result<Yield<I>, Error>
+    impl<I, T, E> ReturnValue for Result<Yield<I>, E>
     where
         I: Iterator<Item = T>,
         T: IntoValue,
+        E: IntoRubyError,
     {
         fn into_return_value(self) -> Result<Value, Error> {
-            self?.into_return_value()
+            self.map_err(|e| e.into_ruby_error(&unsafe { Ruby::get_unchecked() }))?
+                .into_return_value()
         }
     }
 
@@ -134,13 +138,15 @@ mod private {
         }
     }
 
-    impl<I, T> ReturnValue for Result<YieldValues<I>, Error>
+    impl<I, T, E> ReturnValue for Result<YieldValues<I>, E>
     where
         I: Iterator<Item = T>,
         T: ArgList,
+        E: IntoRubyError,
     {
         fn into_return_value(self) -> Result<Value, Error> {
-            self?.into_return_value()
+            self.map_err(|e| e.into_ruby_error(&unsafe { Ruby::get_unchecked() }))?
+                .into_return_value()
         }
     }
 
@@ -159,12 +165,14 @@ mod private {
         }
     }
 
-    impl<I> ReturnValue for Result<YieldSplat<I>, Error>
+    impl<I, E> ReturnValue for Result<YieldSplat<I>, E>
     where
         I: Iterator<Item = RArray>,
+        E: IntoRubyError,
     {
         fn into_return_value(self) -> Result<Value, Error> {
-            self?.into_return_value()
+            self.map_err(|e| e.into_ruby_error(&unsafe { Ruby::get_unchecked() }))?
+                .into_return_value()
         }
     }
 
@@ -243,15 +251,19 @@ impl<T> Method for T where T: private::Method {}
 /// * [`Yield<I>`]
 /// * [`YieldValues<I>`]
 /// * [`YieldSplat<I>`]
-/// * `Result<T, magnus::Error>`
-/// * `Result<Yield<I>, magnus::Error>`
-/// * `Result<YieldValues<I>, magnus::Error>`
-/// * `Result<YieldSplat<I>, magnus::Error>`
+/// * `Result<T, E>`
+/// * `Result<Yield<I>, E>`
+/// * `Result<YieldValues<I>, E>`
+/// * `Result<YieldSplat<I>, E>`
 ///
-/// where `I` implements `Iterator<Item = T>` and `T` implements [`IntoValue`].
+/// where `I` implements `Iterator<Item = T>`, `T` implements [`IntoValue`],
+/// and `E` implements [`IntoRubyError`] (such as [`magnus::Error`](Error)).
 ///
-/// When is `Err(magnus::Error)` returned to Ruby it will be conveted to and
-/// raised as a Ruby exception.
+/// When is `Err(E)` returned to Ruby it will be conveted to and raised as a
+/// Ruby exception.
+///
+/// As `E` is generic, closures returning `Err(e.into())` may need their
+/// return type annotated, e.g. `|x: i64| -> Result<i64, Error> { ... }`.
 ///
 /// [`Yield`], [`YieldValues`], and [`YieldSplat`] allow returning a Rust
 /// [`Iterator`] to be bridged to Ruby method that calls a block with the
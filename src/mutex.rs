@@ -8,25 +8,27 @@ use rb_sys::{
 };
 
 use crate::{
-    class::RClass,
+    class::{Class, RClass},
     error::{protect, Error},
     into_value::IntoValue,
     method::{BlockReturn, Synchronize},
+    module::Module,
     object::Object,
     r_typed_data::RTypedData,
     try_convert::TryConvert,
     value::{
         private::{self, ReprValue as _},
-        ReprValue, Value,
+        Lazy, ReprValue, Value,
     },
     Ruby,
 };
 
 /// # `Mutex`
 ///
-/// Functions that can be used to create Ruby `Mutex`s.
+/// Functions that can be used to create Ruby `Mutex`s and
+/// `ConditionVariable`s.
 ///
-/// See also the [`Mutex`] type.
+/// See also the [`Mutex`] and [`ConditionVariable`] types.
 impl Ruby {
     /// Create a Ruby Mutex.
     ///
@@ -46,6 +48,34 @@ impl Ruby {
     pub fn mutex_new(&self) -> Mutex {
         unsafe { Mutex::from_rb_value_unchecked(rb_mutex_new()) }
     }
+
+    /// Create a Ruby ConditionVariable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cond = ruby.condition_variable_new();
+    ///     cond.signal()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn condition_variable_new(&self) -> ConditionVariable {
+        let val: Value = condition_variable_class(self)
+            .new_instance(())
+            .expect("Thread::ConditionVariable.new raised an error");
+        unsafe { ConditionVariable::from_rb_value_unchecked(val.as_rb_value()) }
+    }
+}
+
+fn condition_variable_class(ruby: &Ruby) -> RClass {
+    static CLASS: Lazy<RClass> =
+        Lazy::new(|ruby| ruby.class_thread().const_get("ConditionVariable").unwrap());
+    ruby.get_inner(&CLASS)
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby's Mutex class.
@@ -161,6 +191,39 @@ impl Mutex {
         Ok(())
     }
 
+    /// Acquires the lock, returning a guard that releases the lock when
+    /// dropped.
+    ///
+    /// This method will block the current thread until the lock can be
+    /// acquired. Returns `Err` on deadlock.
+    ///
+    /// As the lock is released when the guard is dropped, it is also
+    /// released on early return (e.g. with `?`) or panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let lock = ruby.mutex_new();
+    ///
+    ///     let guard = lock.lock_guard()?;
+    ///     assert!(lock.is_locked());
+    ///     assert!(lock.lock_guard().is_err());
+    ///
+    ///     drop(guard);
+    ///     assert!(!lock.is_locked());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn lock_guard(self) -> Result<MutexGuard, Error> {
+        self.lock()?;
+        Ok(MutexGuard { mutex: self })
+    }
+
     /// Release the lock.
     ///
     /// Returns `Err` if the current thread does not own the lock.
@@ -302,3 +365,171 @@ impl TryConvert for Mutex {
         })
     }
 }
+
+/// A guard that releases the lock on a [`Mutex`] when dropped.
+///
+/// See [`Mutex::lock_guard`].
+#[must_use = "if unused the Mutex will immediately unlock"]
+pub struct MutexGuard {
+    mutex: Mutex,
+}
+
+impl MutexGuard {
+    /// Return the [`Mutex`] this guard holds the lock for.
+    pub fn mutex(&self) -> Mutex {
+        self.mutex
+    }
+
+    /// Release the lock for `timeout`, reaquiring it on wakeup.
+    ///
+    /// See [`Mutex::sleep`].
+    pub fn sleep(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.mutex.sleep(timeout)
+    }
+}
+
+impl Drop for MutexGuard {
+    fn drop(&mut self) {
+        // only errors if the current thread no longer holds the lock, e.g.
+        // it was unlocked manually, in which case there's nothing to do
+        let _ = self.mutex.unlock();
+    }
+}
+
+impl fmt::Debug for MutexGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MutexGuard")
+            .field("mutex", &self.mutex)
+            .finish()
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's
+/// `Thread::ConditionVariable` class.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#mutex) for methods to create a
+/// `ConditionVariable`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{value::Opaque, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let lock = ruby.mutex_new();
+///     let cond = ruby.condition_variable_new();
+///     let ready = ruby.ary_new();
+///
+///     let (opaque_lock, opaque_cond, opaque_ready) =
+///         (Opaque::from(lock), Opaque::from(cond), Opaque::from(ready));
+///     ruby.thread_create_from_fn(move |ruby| {
+///         let _guard = ruby.get_inner(opaque_lock).lock_guard()?;
+///         ruby.get_inner(opaque_ready).push(true)?;
+///         ruby.get_inner(opaque_cond).signal()
+///     });
+///
+///     let guard = lock.lock_guard()?;
+///     while ready.is_empty() {
+///         cond.wait(&guard, None)?;
+///     }
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct ConditionVariable(RTypedData);
+
+impl ConditionVariable {
+    /// Return `Some(ConditionVariable)` if `val` is a
+    /// `Thread::ConditionVariable`, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, ConditionVariable};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert!(ConditionVariable::from_value(eval("ConditionVariable.new").unwrap()).is_some());
+    /// assert!(ConditionVariable::from_value(eval("Mutex.new").unwrap()).is_none());
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        let class = condition_variable_class(&Ruby::get_with(val));
+        RTypedData::from_value(val)
+            .filter(|_| val.is_kind_of(class))
+            .map(Self)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn from_rb_value_unchecked(val: VALUE) -> Self {
+        Self(RTypedData::from_rb_value_unchecked(val))
+    }
+
+    /// Release the lock held by `guard` and wait to be woken by
+    /// [`signal`](ConditionVariable::signal) or
+    /// [`broadcast`](ConditionVariable::broadcast), or for `timeout` to pass.
+    /// The lock is reaquired before this method returns.
+    ///
+    /// This uses [`Mutex::sleep`], so wakeups may be spurious, and the
+    /// condition being waited for should be checked in a loop.
+    ///
+    /// See [`ConditionVariable`] for an example.
+    pub fn wait(self, guard: &MutexGuard, timeout: Option<Duration>) -> Result<(), Error> {
+        let _: Value = self.funcall("wait", (guard.mutex(), timeout.map(|d| d.as_secs_f64())))?;
+        Ok(())
+    }
+
+    /// Wake one thread waiting on `self`.
+    pub fn signal(self) -> Result<(), Error> {
+        let _: Value = self.funcall("signal", ())?;
+        Ok(())
+    }
+
+    /// Wake all threads waiting on `self`.
+    pub fn broadcast(self) -> Result<(), Error> {
+        let _: Value = self.funcall("broadcast", ())?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConditionVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for ConditionVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for ConditionVariable {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.as_value()
+    }
+}
+
+impl Object for ConditionVariable {}
+
+unsafe impl private::ReprValue for ConditionVariable {}
+
+impl ReprValue for ConditionVariable {}
+
+impl TryConvert for ConditionVariable {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!(
+                    "no implicit conversion of {} into ConditionVariable",
+                    unsafe { val.classname() },
+                ),
+            )
+        })
+    }
+}
@@ -0,0 +1,671 @@
+//! Types for working with Ruby's `Thread::Queue` and `Thread::SizedQueue`.
+//!
+//! See [`Queue`] and [`SizedQueue`].
+
+use std::{fmt, marker::PhantomData};
+
+#[cfg(any(ruby_gte_3_2, docsrs))]
+use std::time::Duration;
+
+use crate::{
+    class::{Class, RClass},
+    error::Error,
+    into_value::IntoValue,
+    module::Module,
+    object::Object,
+    try_convert::TryConvert,
+    value::{
+        private::{self, ReprValue as _},
+        Lazy, NonZeroValue, ReprValue, Value,
+    },
+    Ruby,
+};
+
+/// # `Queue`
+///
+/// Functions that can be used to create Ruby `Thread::Queue`s and
+/// `Thread::SizedQueue`s.
+///
+/// See also the [`Queue`] and [`SizedQueue`] types.
+impl Ruby {
+    /// Create a Ruby `Thread::Queue`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     queue.push(1)?;
+    ///     assert_eq!(queue.pop()?, Some(1));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn queue_new<T>(&self) -> Queue<T> {
+        let queue: Value = queue_class(self)
+            .new_instance(())
+            .expect("Thread::Queue.new raised an error");
+        unsafe { Queue::from_value_unchecked(queue) }
+    }
+
+    /// Create a Ruby `Thread::SizedQueue`, that holds at most `max` items.
+    ///
+    /// Returns `Err` if `max` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, SizedQueue};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: SizedQueue<i64> = ruby.sized_queue_new(2)?;
+    ///     assert_eq!(queue.max()?, 2);
+    ///     assert!(ruby.sized_queue_new::<i64>(0).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn sized_queue_new<T>(&self, max: usize) -> Result<SizedQueue<T>, Error> {
+        let queue: Value = sized_queue_class(self).new_instance((max,))?;
+        Ok(unsafe { SizedQueue::from_value_unchecked(queue) })
+    }
+}
+
+fn queue_class(ruby: &Ruby) -> RClass {
+    static CLASS: Lazy<RClass> = Lazy::new(|ruby| ruby.class_thread().const_get("Queue").unwrap());
+    ruby.get_inner(&CLASS)
+}
+
+fn sized_queue_class(ruby: &Ruby) -> RClass {
+    static CLASS: Lazy<RClass> =
+        Lazy::new(|ruby| ruby.class_thread().const_get("SizedQueue").unwrap());
+    ruby.get_inner(&CLASS)
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's
+/// `Thread::Queue` class (or its subclass `Thread::SizedQueue`), used to pass
+/// values of type `T` between threads.
+///
+/// Values are converted with [`IntoValue`] when pushed, and [`TryConvert`]
+/// when popped. As Ruby code may push any object to the queue, popping
+/// returns `Err` if a value can not be converted to `T`.
+///
+/// Blocking operations (e.g. [`pop`](Queue::pop)) wait in the same way as
+/// Ruby's `Queue#pop`, so allow other Ruby threads to run, respect the Fiber
+/// scheduler, and can be interrupted (e.g. by `Thread#raise`).
+///
+/// A closed queue returns `nil` when popped once empty, so a queue can not
+/// be used to pass `nil` values.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#queue) for methods to create a
+/// `Queue`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{value::Opaque, Error, Queue, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let queue: Queue<i64> = ruby.queue_new();
+///
+///     let opaque_queue = Opaque::from(queue);
+///     let producer = ruby.thread_create_from_fn(move |ruby| {
+///         let queue = ruby.get_inner(opaque_queue);
+///         for i in 1..=3 {
+///             queue.push(i)?;
+///         }
+///         queue.close()
+///     });
+///
+///     let sum = queue.into_iter().sum::<Result<i64, Error>>()?;
+///     assert_eq!(sum, 6);
+///     producer.join(None)?;
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[repr(transparent)]
+pub struct Queue<T>(NonZeroValue, PhantomData<T>);
+
+impl<T> Queue<T> {
+    /// Return `Some(Queue)` if `val` is a `Thread::Queue`, `None` otherwise.
+    ///
+    /// The contents of the queue are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Queue};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert!(Queue::<i64>::from_value(eval("Queue.new").unwrap()).is_some());
+    /// assert!(Queue::<i64>::from_value(eval("SizedQueue.new(1)").unwrap()).is_some());
+    /// assert!(Queue::<i64>::from_value(eval("[]").unwrap()).is_none());
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        val.is_kind_of(queue_class(&Ruby::get_with(val)))
+            .then(|| unsafe { Self::from_value_unchecked(val) })
+    }
+
+    /// Push `val` on to the queue.
+    ///
+    /// Returns `Err` if the queue is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<String> = ruby.queue_new();
+    ///     queue.push(String::from("example"))?;
+    ///     assert_eq!(queue.len()?, 1);
+    ///
+    ///     queue.close()?;
+    ///     assert!(queue.push(String::from("closed")).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn push(self, val: T) -> Result<(), Error>
+    where
+        T: IntoValue,
+    {
+        let _: Value = self.funcall("push", (val,))?;
+        Ok(())
+    }
+
+    /// Remove and return the value at the front of the queue, blocking until
+    /// one is available.
+    ///
+    /// Returns `Ok(None)` if the queue is closed and empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{value::Opaque, Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     let opaque_queue = Opaque::from(queue);
+    ///     ruby.thread_create_from_fn(move |ruby| ruby.get_inner(opaque_queue).push(42));
+    ///     assert_eq!(queue.pop()?, Some(42));
+    ///
+    ///     queue.close()?;
+    ///     assert_eq!(queue.pop()?, None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn pop(self) -> Result<Option<T>, Error>
+    where
+        T: TryConvert,
+    {
+        let val: Value = self.funcall("pop", ())?;
+        self.popped(val)
+    }
+
+    /// Remove and return the value at the front of the queue, blocking until
+    /// one is available or `timeout` passes.
+    ///
+    /// Returns `Ok(None)` if `timeout` passes, or if the queue is closed and
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use magnus::{Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     assert_eq!(queue.pop_timeout(Duration::from_millis(10))?, None);
+    ///
+    ///     queue.push(1)?;
+    ///     assert_eq!(queue.pop_timeout(Duration::from_millis(10))?, Some(1));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+    pub fn pop_timeout(self, timeout: Duration) -> Result<Option<T>, Error>
+    where
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self);
+        let val: Value = self.funcall(
+            "pop",
+            (crate::kwargs!(&ruby, "timeout" => timeout.as_secs_f64()),),
+        )?;
+        if val.is_nil() {
+            return Ok(None);
+        }
+        T::try_convert(val).map(Some)
+    }
+
+    /// Remove and return the value at the front of the queue, without
+    /// blocking.
+    ///
+    /// Returns `Ok(None)` if the queue is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     assert_eq!(queue.try_pop()?, None);
+    ///
+    ///     queue.push(1)?;
+    ///     assert_eq!(queue.try_pop()?, Some(1));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn try_pop(self) -> Result<Option<T>, Error>
+    where
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self);
+        match self.funcall::<_, _, Value>("pop", (true,)) {
+            Ok(val) => T::try_convert(val).map(Some),
+            // raised when the queue is empty
+            Err(e) if e.is_kind_of(ruby.exception_thread_error()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn popped(self, val: Value) -> Result<Option<T>, Error>
+    where
+        T: TryConvert,
+    {
+        if val.is_nil() && self.is_closed()? {
+            return Ok(None);
+        }
+        T::try_convert(val).map(Some)
+    }
+
+    /// Close the queue.
+    ///
+    /// A closed queue can not be pushed to, and once empty any threads
+    /// waiting to pop from the queue are woken and return `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     assert!(!queue.is_closed()?);
+    ///
+    ///     queue.close()?;
+    ///     assert!(queue.is_closed()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn close(self) -> Result<(), Error> {
+        let _: Value = self.funcall("close", ())?;
+        Ok(())
+    }
+
+    /// Returns whether the queue is closed.
+    ///
+    /// See [`Queue::close`] for an example.
+    pub fn is_closed(self) -> Result<bool, Error> {
+        self.funcall("closed?", ())
+    }
+
+    /// Returns the number of values in the queue.
+    pub fn len(self) -> Result<usize, Error> {
+        self.funcall("length", ())
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_empty(self) -> Result<bool, Error> {
+        self.funcall("empty?", ())
+    }
+
+    /// Remove all values from the queue.
+    pub fn clear(self) -> Result<(), Error> {
+        let _: Value = self.funcall("clear", ())?;
+        Ok(())
+    }
+
+    /// Returns the number of threads waiting on the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{value::Opaque, Error, Queue, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: Queue<i64> = ruby.queue_new();
+    ///     let opaque_queue = Opaque::from(queue);
+    ///     let t = ruby.thread_create_from_fn(move |ruby| ruby.get_inner(opaque_queue).pop());
+    ///     while queue.num_waiting()? == 0 {
+    ///         ruby.thread_schedule();
+    ///     }
+    ///
+    ///     queue.push(1)?;
+    ///     t.join(None)?;
+    ///     assert_eq!(queue.num_waiting()?, 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn num_waiting(self) -> Result<usize, Error> {
+        self.funcall("num_waiting", ())
+    }
+}
+
+impl<T> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Queue<T> {}
+
+impl<T> IntoIterator for Queue<T>
+where
+    T: TryConvert,
+{
+    type Item = Result<T, Error>;
+    type IntoIter = Iter<T>;
+
+    /// Returns an iterator that pops values from the queue, blocking while
+    /// the queue is empty, and ending once the queue is closed and empty.
+    fn into_iter(self) -> Self::IntoIter {
+        Iter(self)
+    }
+}
+
+/// An iterator that pops values from a [`Queue`] or [`SizedQueue`] until it
+/// is closed.
+///
+/// See the [`Queue`] for an example.
+pub struct Iter<T>(Queue<T>);
+
+impl<T> Iterator for Iter<T>
+where
+    T: TryConvert,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop().transpose()
+    }
+}
+
+impl<T> fmt::Display for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl<T> fmt::Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl<T> IntoValue for Queue<T> {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.get()
+    }
+}
+
+impl<T> Object for Queue<T> {}
+
+unsafe impl<T> private::ReprValue for Queue<T> {}
+
+impl<T> ReprValue for Queue<T> {}
+
+impl<T> TryConvert for Queue<T> {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into Thread::Queue", unsafe {
+                    val.classname()
+                },),
+            )
+        })
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's
+/// `Thread::SizedQueue` class, a [`Queue`] with a maximum size.
+///
+/// Pushing to a full `SizedQueue` blocks until space is available. All other
+/// methods are the same as [`Queue`], which can be obtained with
+/// [`as_queue`](SizedQueue::as_queue).
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#queue) for methods to create a
+/// `SizedQueue`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{value::Opaque, Error, Ruby, SizedQueue};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let queue: SizedQueue<i64> = ruby.sized_queue_new(1)?;
+///
+///     let opaque_queue = Opaque::from(queue);
+///     let producer = ruby.thread_create_from_fn(move |ruby| {
+///         let queue = ruby.get_inner(opaque_queue);
+///         for i in 1..=3 {
+///             // blocks until the previous value is popped
+///             queue.push(i)?;
+///         }
+///         queue.close()
+///     });
+///
+///     let values = queue.into_iter().collect::<Result<Vec<i64>, Error>>()?;
+///     assert_eq!(values, [1, 2, 3]);
+///     producer.join(None)?;
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[repr(transparent)]
+pub struct SizedQueue<T>(Queue<T>);
+
+macro_rules! proxy {
+    ($(#[$attr:meta])* $method:ident($($arg:ident: $typ:ty),*) -> $ret:ty $(where T: $bound:ident)?) => {
+        #[doc=concat!("See [`Queue::", stringify!($method), "`].")]
+        $(#[$attr])*
+        pub fn $method(self, $($arg: $typ),*) -> $ret
+        $(where
+            T: $bound,)?
+        {
+            self.0.$method($($arg),*)
+        }
+    };
+}
+
+impl<T> SizedQueue<T> {
+    /// Return `Some(SizedQueue)` if `val` is a `Thread::SizedQueue`, `None`
+    /// otherwise.
+    ///
+    /// The contents of the queue are not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, SizedQueue};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert!(SizedQueue::<i64>::from_value(eval("SizedQueue.new(1)").unwrap()).is_some());
+    /// assert!(SizedQueue::<i64>::from_value(eval("Queue.new").unwrap()).is_none());
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        val.is_kind_of(sized_queue_class(&Ruby::get_with(val)))
+            .then(|| unsafe { Self::from_value_unchecked(val) })
+    }
+
+    /// Return `self` as a [`Queue`].
+    pub fn as_queue(self) -> Queue<T> {
+        self.0
+    }
+
+    /// Push `val` on to the queue, blocking while the queue is full.
+    ///
+    /// Returns `Err` if the queue is closed.
+    pub fn push(self, val: T) -> Result<(), Error>
+    where
+        T: IntoValue,
+    {
+        self.0.push(val)
+    }
+
+    /// Push `val` on to the queue, blocking while the queue is full, until
+    /// `timeout` passes.
+    ///
+    /// Returns `Ok(false)` if `timeout` passes before `val` could be pushed.
+    /// Returns `Err` if the queue is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use magnus::{Error, Ruby, SizedQueue};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let queue: SizedQueue<i64> = ruby.sized_queue_new(1)?;
+    ///     assert!(queue.push_timeout(1, Duration::from_millis(10))?);
+    ///     assert!(!queue.push_timeout(2, Duration::from_millis(10))?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_2, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+    pub fn push_timeout(self, val: T, timeout: Duration) -> Result<bool, Error>
+    where
+        T: IntoValue,
+    {
+        let ruby = Ruby::get_with(self);
+        let res: Value = self.funcall(
+            "push",
+            (
+                val,
+                crate::kwargs!(&ruby, "timeout" => timeout.as_secs_f64()),
+            ),
+        )?;
+        Ok(!res.is_nil())
+    }
+
+    /// Returns the maximum size of the queue.
+    pub fn max(self) -> Result<usize, Error> {
+        self.funcall("max", ())
+    }
+
+    /// Set the maximum size of the queue.
+    ///
+    /// Returns `Err` if `max` is zero.
+    pub fn set_max(self, max: usize) -> Result<(), Error> {
+        let _: Value = self.funcall("max=", (max,))?;
+        Ok(())
+    }
+
+    proxy!(pop() -> Result<Option<T>, Error> where T: TryConvert);
+    proxy!(
+        #[cfg(any(ruby_gte_3_2, docsrs))]
+        #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_2)))]
+        pop_timeout(timeout: Duration) -> Result<Option<T>, Error> where T: TryConvert
+    );
+    proxy!(try_pop() -> Result<Option<T>, Error> where T: TryConvert);
+    proxy!(close() -> Result<(), Error>);
+    proxy!(is_closed() -> Result<bool, Error>);
+    proxy!(len() -> Result<usize, Error>);
+    proxy!(is_empty() -> Result<bool, Error>);
+    proxy!(clear() -> Result<(), Error>);
+    proxy!(num_waiting() -> Result<usize, Error>);
+}
+
+impl<T> Clone for SizedQueue<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SizedQueue<T> {}
+
+impl<T> IntoIterator for SizedQueue<T>
+where
+    T: TryConvert,
+{
+    type Item = Result<T, Error>;
+    type IntoIter = Iter<T>;
+
+    /// Returns an iterator that pops values from the queue, blocking while
+    /// the queue is empty, and ending once the queue is closed and empty.
+    fn into_iter(self) -> Self::IntoIter {
+        Iter(self.0)
+    }
+}
+
+impl<T> fmt::Display for SizedQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl<T> fmt::Debug for SizedQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl<T> IntoValue for SizedQueue<T> {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        self.0.into_value_with(handle)
+    }
+}
+
+impl<T> Object for SizedQueue<T> {}
+
+unsafe impl<T> private::ReprValue for SizedQueue<T> {}
+
+impl<T> ReprValue for SizedQueue<T> {}
+
+impl<T> TryConvert for SizedQueue<T> {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!(
+                    "no implicit conversion of {} into Thread::SizedQueue",
+                    unsafe { val.classname() },
+                ),
+            )
+        })
+    }
+}
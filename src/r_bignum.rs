@@ -399,6 +399,47 @@ impl RBignum {
         Ok(res as usize)
     }
 
+    /// Returns the magnitude of `self` as a byte array in little-endian byte
+    /// order.
+    ///
+    /// The returned bytes do not encode the sign of `self`, see
+    /// [`is_negative`](RBignum::is_negative).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, RBignum};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// let num = eval::<RBignum>("2 ** 64 + 1").unwrap();
+    /// assert_eq!(num.to_bytes_le(), [1, 0, 0, 0, 0, 0, 0, 0, 1]);
+    /// ```
+    pub fn to_bytes_le(self) -> Vec<u8> {
+        debug_assert_value!(self);
+        unsafe { Integer::from_rb_value_unchecked(self.as_rb_value()) }.to_bytes_le()
+    }
+
+    /// Returns the magnitude of `self` as a byte array in big-endian byte
+    /// order.
+    ///
+    /// The returned bytes do not encode the sign of `self`, see
+    /// [`is_negative`](RBignum::is_negative).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, RBignum};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// let num = eval::<RBignum>("-(2 ** 64 + 1)").unwrap();
+    /// assert_eq!(num.to_bytes_be(), [1, 0, 0, 0, 0, 0, 0, 0, 1]);
+    /// assert!(num.is_negative());
+    /// ```
+    pub fn to_bytes_be(self) -> Vec<u8> {
+        debug_assert_value!(self);
+        unsafe { Integer::from_rb_value_unchecked(self.as_rb_value()) }.to_bytes_be()
+    }
+
     /// Check if `self` is positive.
     ///
     /// # Examples
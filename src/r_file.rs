@@ -1,11 +1,20 @@
 use std::fmt;
 #[cfg(ruby_lt_3_3)]
 use std::ptr::NonNull;
+#[cfg(unix)]
+use std::{
+    fs::File,
+    os::unix::io::{BorrowedFd, IntoRawFd, OwnedFd},
+};
 
 #[cfg(ruby_gte_3_3)]
 use rb_sys::rb_io_descriptor;
+#[cfg(unix)]
+use rb_sys::rb_io_get_io;
 use rb_sys::ruby_value_type;
 
+#[cfg(unix)]
+use crate::{class::RClass, error::protect};
 use crate::{
     error::Error,
     into_value::IntoValue,
@@ -18,6 +27,65 @@ use crate::{
     Ruby,
 };
 
+/// # `IO`
+///
+/// Functions to create Ruby `IO` objects from file descriptors.
+///
+/// See also the [`RFile`] type.
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+impl Ruby {
+    /// Create a new Ruby `IO` from `fd`, such as a [`File`],
+    /// [`TcpStream`](std::net::TcpStream), or [`OwnedFd`].
+    ///
+    /// The `IO` takes ownership of `fd`, and will close it when the `IO` is
+    /// closed or garbage collected.
+    ///
+    /// `mode` is a Ruby mode string, such as `"r"`, `"wb"`, or
+    /// `"r:UTF-8"`, which may include the external/internal encodings. When
+    /// `None` the mode is taken from the file descriptor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{io::Write, os::unix::net::UnixStream};
+    ///
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let (a, mut b) = UnixStream::pair().unwrap();
+    ///     let io = ruby.io_from_fd(a, Some("r:UTF-8"))?;
+    ///     b.write_all("caf\u{e9}\n".as_bytes()).unwrap();
+    ///
+    ///     rb_assert!(ruby, r#"io.gets == "caf\u00e9\n""#, io);
+    ///     rb_assert!(ruby, "io.external_encoding == Encoding::UTF_8", io);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn io_from_fd<T>(&self, fd: T, mode: Option<&str>) -> Result<RFile, Error>
+    where
+        T: Into<OwnedFd>,
+    {
+        self.io_for_fd(self.class_io(), fd.into(), mode)
+    }
+
+    fn io_for_fd(&self, class: RClass, fd: OwnedFd, mode: Option<&str>) -> Result<RFile, Error> {
+        use std::os::unix::io::AsRawFd;
+
+        let kwargs = crate::kwargs!(self, "autoclose" => true);
+        // if this errors `fd` is dropped, and so closed
+        let io = match mode {
+            Some(mode) => class.funcall("for_fd", (fd.as_raw_fd(), mode, kwargs)),
+            None => class.funcall("for_fd", (fd.as_raw_fd(), kwargs)),
+        }?;
+        // the IO now owns the file descriptor
+        let _ = fd.into_raw_fd();
+        Ok(io)
+    }
+}
+
 /// A Value pointer to a RFile struct, Ruby's internal representation of IO.
 ///
 /// See the [`ReprValue`] and [`Object`] traits for additional methods
@@ -78,6 +146,26 @@ impl IntoValue for RFile {
     }
 }
 
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+impl IntoValue for OwnedFd {
+    #[inline]
+    fn into_value_with(self, ruby: &Ruby) -> Value {
+        ruby.io_from_fd(self, None).unwrap().as_value()
+    }
+}
+
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+impl IntoValue for File {
+    #[inline]
+    fn into_value_with(self, ruby: &Ruby) -> Value {
+        ruby.io_for_fd(ruby.class_file(), self.into(), None)
+            .unwrap()
+            .as_value()
+    }
+}
+
 impl Object for RFile {}
 
 unsafe impl private::ReprValue for RFile {}
@@ -97,6 +185,45 @@ impl TryConvert for RFile {
     }
 }
 
+/// Borrow the file descriptor of a Ruby `IO`, or an object that can be
+/// implicitly converted to an `IO` with `to_io`.
+///
+/// The file descriptor is only valid as long as the `IO` is kept alive and not
+/// closed, so this is intended for use as an argument to a function bound as
+/// a Ruby method, where the `IO` will be live for the duration of the call.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::io::{AsRawFd, BorrowedFd};
+///
+/// use magnus::{function, rb_assert, Error, Ruby};
+///
+/// fn raw_fd(fd: BorrowedFd) -> i32 {
+///     fd.as_raw_fd()
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("raw_fd", function!(raw_fd, 1));
+///
+///     rb_assert!(ruby, "raw_fd(STDOUT) == 1");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+impl TryConvert for BorrowedFd<'_> {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        let io = protect(|| unsafe { Value::new(rb_io_get_io(val.as_rb_value())) })?;
+        // `fileno` raises IOError if closed, so fd will never be -1.
+        // `funcall` is protected, so that is returned as `Err`
+        let fd: i32 = io.funcall("fileno", ())?;
+        Ok(unsafe { BorrowedFd::borrow_raw(fd) })
+    }
+}
+
 #[cfg(not(unix))]
 pub mod fd {
     use std::os::raw::c_int;
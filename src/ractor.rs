@@ -0,0 +1,295 @@
+//! Types and functions for working with Ruby's Ractors.
+//!
+//! See also [`Ruby`](Ruby#ractor) for more Ractor related methods.
+
+#[cfg(ruby_gte_3_0)]
+use std::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    ptr,
+    sync::{atomic::Ordering, Once},
+};
+
+#[cfg(ruby_gte_3_0)]
+use rb_sys::{
+    rb_ext_ractor_safe, rb_ractor_local_key_t, rb_ractor_local_storage_ptr,
+    rb_ractor_local_storage_ptr_newkey, rb_ractor_local_storage_ptr_set,
+    rb_ractor_local_storage_type, rb_ractor_make_shareable, rb_ractor_make_shareable_copy,
+    rb_ractor_shareable_p_continue, ruby_fl_type, VALUE,
+};
+
+use crate::api::Ruby;
+#[cfg(ruby_gte_3_0)]
+use crate::{
+    api::RACTOR_SAFE,
+    error::{protect, Error},
+    typed_data::DataTypeFunctions,
+    value::{private::ReprValue as _, ReprValue, Value},
+};
+
+/// # Ractor
+///
+/// Functions for working with Ruby's Ractors.
+impl Ruby {
+    /// Declare the extension currently being loaded as Ractor safe.
+    ///
+    /// Methods defined after this is called with `true` may be called from
+    /// non-main Ractors. This should only be called from an extension's init
+    /// function, and usually it is simpler to use the `ractor_safe` flag of
+    /// the [`init`](macro@crate::init) macro.
+    ///
+    /// On Ruby versions before 3.0 this does nothing, as Ractors do not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, Error, Ruby};
+    ///
+    /// fn hello(subject: String) -> String {
+    ///     format!("hello, {}", subject)
+    /// }
+    ///
+    /// #[magnus::init]
+    /// fn init(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.ext_ractor_safe(true);
+    ///     ruby.define_global_function("hello", function!(hello, 1));
+    ///     Ok(())
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    pub fn ext_ractor_safe(&self, flag: bool) {
+        #[cfg(ruby_gte_3_0)]
+        {
+            if flag {
+                RACTOR_SAFE.store(true, Ordering::Relaxed);
+            }
+            unsafe { rb_ext_ractor_safe(flag) };
+        }
+    }
+
+    /// Make `val` shareable between Ractors, deeply freezing it and all the
+    /// objects it references.
+    ///
+    /// Returns `val` once it is shareable. Returns an error if `val` (or a
+    /// referenced object) can not be made shareable.
+    ///
+    /// Equivalent to Ruby's `Ractor.make_shareable(val)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![ruby.str_new("a"), ruby.str_new("b")]);
+    ///     assert!(!ruby.is_shareable(ary));
+    ///
+    ///     let ary = ruby.make_shareable(ary)?;
+    ///     assert!(ruby.is_shareable(ary));
+    ///     assert!(ary.is_frozen());
+    ///     assert!(ary.entry::<magnus::Value>(0)?.is_frozen());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_0, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+    pub fn make_shareable<T>(&self, val: T) -> Result<T, Error>
+    where
+        T: ReprValue,
+    {
+        protect(|| unsafe { Value::new(rb_ractor_make_shareable(val.as_rb_value())) })?;
+        Ok(val)
+    }
+
+    /// Return a deep copy of `val` that is shareable between Ractors.
+    ///
+    /// `val` itself is not modified. Returns an error if `val` (or a
+    /// referenced object) can not be copied or made shareable.
+    ///
+    /// Equivalent to Ruby's `Ractor.make_shareable(val, copy: true)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("example");
+    ///
+    ///     let copy = ruby.make_shareable_copy(s)?;
+    ///     assert!(ruby.is_shareable(copy));
+    ///     assert!(!s.is_frozen());
+    ///     assert!(copy.equal(s)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_0, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+    pub fn make_shareable_copy<T>(&self, val: T) -> Result<T, Error>
+    where
+        T: ReprValue,
+    {
+        protect(|| unsafe { Value::new(rb_ractor_make_shareable_copy(val.as_rb_value())) })
+            .map(|v| unsafe { T::from_value_unchecked(v) })
+    }
+
+    /// Check if `val` can be shared between Ractors.
+    ///
+    /// Immediate values (such as `nil`, small integers, and static symbols)
+    /// are always shareable, as are frozen objects that only reference other
+    /// shareable objects.
+    ///
+    /// Equivalent to Ruby's `Ractor.shareable?(val)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.is_shareable(ruby.qnil()));
+    ///     assert!(ruby.is_shareable(ruby.integer_from_i64(42)));
+    ///
+    ///     let s = ruby.str_new("example");
+    ///     assert!(!ruby.is_shareable(s));
+    ///     s.freeze();
+    ///     assert!(ruby.is_shareable(s));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_0, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+    pub fn is_shareable<T>(&self, val: T) -> bool
+    where
+        T: ReprValue,
+    {
+        match val.r_basic() {
+            None => true,
+            Some(r_basic) => unsafe {
+                r_basic.as_ref().flags & ruby_fl_type::RUBY_FL_SHAREABLE as VALUE != 0
+                    || rb_ractor_shareable_p_continue(val.as_rb_value())
+            },
+        }
+    }
+}
+
+/// Lazily initialised Rust data, with one instance per Ractor.
+///
+/// A `RactorLocal<T>` is assigned to a `static`, and the first time it is
+/// accessed from a Ractor it is initialised for that Ractor. This is useful
+/// for state such as caches that would otherwise be global, and so be shared
+/// between (or unsafe to use from) Ractors.
+///
+/// The inner value is marked with Ruby's garbage collector with
+/// [`DataTypeFunctions::mark`], and freed with [`DataTypeFunctions::free`]
+/// when the Ractor terminates.
+///
+/// As the inner value is only available by shared reference, use types such
+/// as [`Cell`](std::cell::Cell) or [`RefCell`](std::cell::RefCell) for data
+/// that needs to be updated.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::RefCell, collections::HashMap};
+///
+/// use magnus::{
+///     gc, ractor::RactorLocal, rb_assert, value::Opaque, DataTypeFunctions, Error, RString, Ruby,
+/// };
+///
+/// #[derive(Default)]
+/// struct Interned(RefCell<HashMap<String, Opaque<RString>>>);
+///
+/// impl DataTypeFunctions for Interned {
+///     fn mark(&self, marker: &gc::Marker) {
+///         for s in self.0.borrow().values() {
+///             marker.mark(*s);
+///         }
+///     }
+/// }
+///
+/// static INTERNED: RactorLocal<Interned> = RactorLocal::new(|_ruby| Interned::default());
+///
+/// fn intern(ruby: &Ruby, s: String) -> RString {
+///     let interned = INTERNED.get(ruby);
+///     if let Some(val) = interned.0.borrow().get(&s) {
+///         return ruby.get_inner(*val);
+///     }
+///     // allocate before borrowing mutably, as the GC may run and mark
+///     let val = ruby.str_new(&s);
+///     interned.0.borrow_mut().insert(s, val.into());
+///     val
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let a = intern(ruby, String::from("example"));
+///     let b = intern(ruby, String::from("example"));
+///     rb_assert!(ruby, "a.equal?(b)", a, b);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[cfg(any(ruby_gte_3_0, docsrs))]
+#[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+pub struct RactorLocal<T> {
+    init: Once,
+    key: UnsafeCell<rb_ractor_local_key_t>,
+    storage_type: rb_ractor_local_storage_type,
+    func: fn(&Ruby) -> T,
+}
+
+#[cfg(any(ruby_gte_3_0, docsrs))]
+impl<T> RactorLocal<T>
+where
+    T: DataTypeFunctions,
+{
+    /// Create a new `RactorLocal<T>`.
+    ///
+    /// This function can be called in a `const` context. `func` is evaluated
+    /// once per Ractor, when the `RactorLocal<T>` is first accessed from that
+    /// Ractor.
+    pub const fn new(func: fn(&Ruby) -> T) -> Self {
+        Self {
+            init: Once::new(),
+            key: UnsafeCell::new(ptr::null_mut()),
+            storage_type: rb_ractor_local_storage_type {
+                mark: Some(T::extern_mark),
+                free: Some(T::extern_free),
+            },
+            func,
+        }
+    }
+
+    /// Get a reference to the instance of `T` for the current Ractor,
+    /// initialising it if required.
+    pub fn get<'a>(&'static self, handle: &'a Ruby) -> &'a T {
+        unsafe {
+            self.init.call_once(|| {
+                *self.key.get() = rb_ractor_local_storage_ptr_newkey(&self.storage_type);
+            });
+            let key = *self.key.get();
+            let mut ptr = rb_ractor_local_storage_ptr(key) as *const T;
+            if ptr.is_null() {
+                let value = (self.func)(handle);
+                // `func` may have accessed `self`, in which case keep the
+                // value it initialised
+                ptr = rb_ractor_local_storage_ptr(key) as *const T;
+                if ptr.is_null() {
+                    ptr = Box::into_raw(Box::new(value));
+                    rb_ractor_local_storage_ptr_set(key, ptr as *mut c_void);
+                }
+            }
+            &*ptr
+        }
+    }
+}
+
+#[cfg(any(ruby_gte_3_0, docsrs))]
+unsafe impl<T> Sync for RactorLocal<T> where T: DataTypeFunctions {}
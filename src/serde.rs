@@ -0,0 +1,262 @@
+//! Serialise and deserialise Rust types to and from Ruby objects with
+//! [serde](::serde).
+//!
+//! Rust values are mapped to Ruby objects as follows:
+//!
+//! | Rust (serde data model)           | Ruby                                  |
+//! |-----------------------------------|---------------------------------------|
+//! | `bool`                            | `true`/`false`                        |
+//! | integers                          | `Integer`                             |
+//! | `f32`, `f64`                      | `Float`                               |
+//! | `char`, `str`                     | `String` (UTF-8)                      |
+//! | bytes                             | `String` (ASCII-8BIT)                 |
+//! | `None`, unit, unit struct         | `nil`                                 |
+//! | `Some(v)`, newtype struct         | `v`                                   |
+//! | sequences, tuples, tuple structs  | `Array`                               |
+//! | maps                              | `Hash`                                |
+//! | structs                           | `Hash` with `Symbol` keys             |
+//! | unit variant                      | `Symbol`, e.g. `:Variant`             |
+//! | other variants                    | `Hash`, e.g. `{Variant: value}`       |
+//!
+//! When deserialising, `Symbol` and `String` are interchangeable, so a struct
+//! can be deserialised from a Hash with either `Symbol` or `String` keys, and
+//! unit variants from either a `Symbol` or a `String`.
+//!
+//! Errors record the path to the value that caused the error, see
+//! [`Error::path`].
+//!
+//! # Examples
+//!
+//! ```
+//! use magnus::{rb_assert, Error, Ruby};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Point {
+//!     x: i64,
+//!     y: i64,
+//! }
+//!
+//! fn example(ruby: &Ruby) -> Result<(), Error> {
+//!     let val = magnus::serde::to_value(ruby, &Point { x: 1, y: 2 })?;
+//!     rb_assert!(ruby, "val == {x: 1, y: 2}", val);
+//!
+//!     let point: Point = magnus::serde::from_value(val)?;
+//!     assert_eq!(point, Point { x: 1, y: 2 });
+//!
+//!     Ok(())
+//! }
+//! # Ruby::init(example).unwrap()
+//! ```
+
+mod de;
+mod ser;
+
+use std::fmt;
+
+use crate::{
+    api::Ruby,
+    error::Error as RubyError,
+    value::{ReprValue, Value},
+};
+
+/// Serialise `value` to a Ruby object.
+///
+/// See the [module level documentation](self) for how Rust values are mapped
+/// to Ruby.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use magnus::{rb_assert, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let mut map = HashMap::new();
+///     map.insert("a", vec![1, 2, 3]);
+///
+///     let val = magnus::serde::to_value(ruby, &map)?;
+///     rb_assert!(ruby, r#"val == {"a" => [1, 2, 3]}"#, val);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_value<T>(handle: &Ruby, value: &T) -> Result<Value, Error>
+where
+    T: ::serde::Serialize + ?Sized,
+{
+    value.serialize(ser::Serializer::new(handle))
+}
+
+/// Deserialise a Rust value from the Ruby object `val`.
+///
+/// See the [module level documentation](self) for how Ruby objects are
+/// mapped to Rust.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val = ruby.eval(r#"[["a", 1.5], ["b", 2.5]]"#)?;
+///     let pairs: Vec<(String, f64)> = magnus::serde::from_value(val)?;
+///     assert_eq!(pairs, [(String::from("a"), 1.5), (String::from("b"), 2.5)]);
+///
+///     let val = ruby.eval(r#"[["a", 1.5], ["b", "c"]]"#)?;
+///     let err = magnus::serde::from_value::<Vec<(String, f64)>, _>(val).unwrap_err();
+///     assert_eq!(err.path(), "[1][1]");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn from_value<T, U>(val: U) -> Result<T, Error>
+where
+    T: ::serde::de::DeserializeOwned,
+    U: ReprValue,
+{
+    T::deserialize(de::Deserializer::new(val.as_value()))
+}
+
+enum ErrorKind {
+    Ruby(RubyError),
+    Message(String),
+}
+
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// An error encountered when serialising or deserialising with [`to_value`]
+/// or [`from_value`].
+///
+/// Can be converted to a [`magnus::Error`](RubyError) with `?` or
+/// [`From`]/[`Into`]. Errors from Ruby are converted back to the original
+/// exception, other errors are converted to a `TypeError`. In both cases the
+/// message will include the path to the value that caused the error.
+pub struct Error {
+    kind: ErrorKind,
+    // innermost segment first
+    path: Vec<PathSegment>,
+}
+
+impl Error {
+    fn new(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            path: Vec::new(),
+        }
+    }
+
+    fn with_field<T>(mut self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.path.push(PathSegment::Field(name.into()));
+        self
+    }
+
+    fn with_index(mut self, index: usize) -> Self {
+        self.path.push(PathSegment::Index(index));
+        self
+    }
+
+    /// Returns the path to the value that caused the error, such as
+    /// `items[2].name`.
+    ///
+    /// Returns an empty string if the error was caused by the outermost
+    /// value.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in self.path.iter().rev() {
+            match segment {
+                PathSegment::Field(name) if path.is_empty() => path.push_str(name),
+                PathSegment::Field(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                PathSegment::Index(i) => {
+                    path.push('[');
+                    path.push_str(&i.to_string());
+                    path.push(']');
+                }
+            }
+        }
+        path
+    }
+
+    fn message(&self) -> String {
+        let path = self.path();
+        let msg = match &self.kind {
+            ErrorKind::Ruby(e) => e.to_string(),
+            ErrorKind::Message(m) => m.clone(),
+        };
+        if path.is_empty() {
+            msg
+        } else {
+            format!("{} at {}", msg, path)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("message", &self.message())
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self::new(ErrorKind::Message(msg.to_string()))
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self::new(ErrorKind::Message(msg.to_string()))
+    }
+}
+
+impl From<RubyError> for Error {
+    fn from(val: RubyError) -> Self {
+        Self::new(ErrorKind::Ruby(val))
+    }
+}
+
+impl From<Error> for RubyError {
+    fn from(val: Error) -> Self {
+        let path = val.path();
+        match val.kind {
+            ErrorKind::Ruby(e) if path.is_empty() => e,
+            ErrorKind::Ruby(e) => e.map_message(|m| format!("{} at {}", m, path)),
+            ErrorKind::Message(m) if path.is_empty() => {
+                RubyError::new(unsafe { Ruby::get_unchecked() }.exception_type_error(), m)
+            }
+            ErrorKind::Message(m) => RubyError::new(
+                unsafe { Ruby::get_unchecked() }.exception_type_error(),
+                format!("{} at {}", m, path),
+            ),
+        }
+    }
+}
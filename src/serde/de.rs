@@ -0,0 +1,353 @@
+use ::serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, Visitor},
+    forward_to_deserialize_any,
+};
+
+use super::Error;
+use crate::{
+    api::Ruby,
+    encoding::EncodingCapable,
+    float::Float,
+    integer::Integer,
+    r_array::RArray,
+    r_hash::RHash,
+    r_string::RString,
+    symbol::Symbol,
+    value::{private::ReprValue as _, ReprValue, Value},
+};
+
+pub struct Deserializer {
+    val: Value,
+}
+
+impl Deserializer {
+    pub fn new(val: Value) -> Self {
+        Self { val }
+    }
+
+    /// Returns the contents of a Symbol or String, for enum variants.
+    fn variant_name(&self) -> Result<Option<String>, Error> {
+        if let Some(sym) = Symbol::from_value(self.val) {
+            return Ok(Some(sym.name()?.into_owned()));
+        }
+        if let Some(s) = RString::from_value(self.val) {
+            return Ok(Some(s.to_string()?));
+        }
+        Ok(None)
+    }
+
+    fn unsupported(&self) -> Error {
+        de::Error::custom(format_args!(
+            "can't deserialize {}",
+            self.val.class().inspect()
+        ))
+    }
+}
+
+/// Returns true if `s` is a binary String, which is deserialised as bytes.
+fn is_binary(s: RString) -> bool {
+    s.enc_get() == Ruby::get_with(s).ascii8bit_encindex()
+}
+
+/// Returns a representation of a Hash key suitable for use in an error path.
+fn key_name(key: Value) -> String {
+    if let Some(sym) = Symbol::from_value(key) {
+        if let Ok(name) = sym.name() {
+            return name.into_owned();
+        }
+    }
+    if let Some(s) = RString::from_value(key) {
+        if let Ok(s) = s.to_string() {
+            return s;
+        }
+    }
+    key.inspect()
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let val = self.val;
+        if val.is_nil() {
+            return visitor.visit_unit();
+        }
+        if val.is_true() {
+            return visitor.visit_bool(true);
+        }
+        if val.is_false() {
+            return visitor.visit_bool(false);
+        }
+        if let Some(i) = Integer::from_value(val) {
+            if let Ok(v) = i.to_i64() {
+                return visitor.visit_i64(v);
+            }
+            if let Ok(v) = i.to_u64() {
+                return visitor.visit_u64(v);
+            }
+            if let Ok(v) = i.to_i128() {
+                return visitor.visit_i128(v);
+            }
+            return visitor.visit_u128(i.to_u128()?);
+        }
+        if let Some(f) = Float::from_value(val) {
+            return visitor.visit_f64(f.to_f64());
+        }
+        if let Some(s) = RString::from_value(val) {
+            if is_binary(s) {
+                return visitor.visit_byte_buf(unsafe { s.as_slice() }.to_vec());
+            }
+            return visitor.visit_string(s.to_string()?);
+        }
+        if let Some(sym) = Symbol::from_value(val) {
+            return visitor.visit_string(sym.name()?.into_owned());
+        }
+        if let Some(ary) = RArray::from_value(val) {
+            return visitor.visit_seq(SeqAccess::new(ary));
+        }
+        if let Some(hash) = RHash::from_value(val) {
+            return visitor.visit_map(MapAccess::new(hash)?);
+        }
+        Err(self.unsupported())
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match RString::from_value(self.val) {
+            Some(s) => visitor.visit_byte_buf(unsafe { s.as_slice() }.to_vec()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.val.is_nil() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if let Some(name) = self.variant_name()? {
+            return visitor.visit_enum(name.into_deserializer());
+        }
+        if let Some(hash) = RHash::from_value(self.val) {
+            if let [(variant, val)] = hash.to_vec::<Value, Value>()?.as_slice() {
+                let variant = match Deserializer::new(*variant).variant_name()? {
+                    Some(v) => v,
+                    None => return Err(Deserializer::new(*variant).unsupported()),
+                };
+                return visitor.visit_enum(EnumAccess { variant, val: *val });
+            }
+        }
+        Err(de::Error::custom(format_args!(
+            "expected a Symbol, String, or Hash with a single key, got {}",
+            self.val.inspect()
+        )))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqAccess {
+    ary: RArray,
+    index: usize,
+}
+
+impl SeqAccess {
+    fn new(ary: RArray) -> Self {
+        Self { ary, index: 0 }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let index = self.index;
+        if index >= self.ary.len() {
+            return Ok(None);
+        }
+        self.index += 1;
+        let val = self.ary.entry::<Value>(index as isize)?;
+        seed.deserialize(Deserializer::new(val))
+            .map(Some)
+            .map_err(|e| e.with_index(index))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.ary.len().saturating_sub(self.index))
+    }
+}
+
+struct MapAccess {
+    // keeps the keys and values below referenced, and so not GC'd
+    #[allow(dead_code)]
+    hash: RHash,
+    entries: std::vec::IntoIter<(Value, Value)>,
+    current: Option<(Value, Value)>,
+}
+
+impl MapAccess {
+    fn new(hash: RHash) -> Result<Self, Error> {
+        Ok(Self {
+            hash,
+            entries: hash.to_vec()?.into_iter(),
+            current: None,
+        })
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.current = self.entries.next();
+        match self.current {
+            Some((key, _)) => seed
+                .deserialize(Deserializer::new(key))
+                .map(Some)
+                .map_err(|e| e.with_field(key_name(key))),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (key, val) = self
+            .current
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::new(val))
+            .map_err(|e| e.with_field(key_name(key)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    val: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantAccess), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.clone().into_deserializer())?;
+        Ok((
+            variant,
+            VariantAccess {
+                variant: self.variant,
+                val: self.val,
+            },
+        ))
+    }
+}
+
+struct VariantAccess {
+    variant: String,
+    val: Value,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        if self.val.is_nil() {
+            Ok(())
+        } else {
+            let e: Error = de::Error::invalid_type(de::Unexpected::Map, &"a Symbol or String");
+            Err(e.with_field(self.variant))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let variant = self.variant;
+        seed.deserialize(Deserializer::new(self.val))
+            .map_err(|e| e.with_field(variant))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let variant = self.variant;
+        de::Deserializer::deserialize_seq(Deserializer::new(self.val), visitor)
+            .map_err(|e| e.with_field(variant))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let variant = self.variant;
+        de::Deserializer::deserialize_map(Deserializer::new(self.val), visitor)
+            .map_err(|e| e.with_field(variant))
+    }
+}
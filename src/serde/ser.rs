@@ -0,0 +1,405 @@
+use ::serde::ser::{self, Serialize};
+
+use super::Error;
+use crate::{
+    api::Ruby,
+    into_value::IntoValue,
+    r_array::RArray,
+    r_hash::RHash,
+    value::{ReprValue, Value},
+};
+
+pub struct Serializer<'a> {
+    handle: &'a Ruby,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(handle: &'a Ruby) -> Self {
+        Self { handle }
+    }
+
+    /// Wrap `val` in a single entry Hash, keyed by `variant`.
+    fn tagged(&self, variant: &'static str, val: Value) -> Result<Value, Error> {
+        let hash = self.handle.hash_new();
+        hash.aset(self.handle.to_symbol(variant), val)?;
+        Ok(hash.as_value())
+    }
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeSeq<'a>;
+    type SerializeTuple = SerializeSeq<'a>;
+    type SerializeTupleStruct = SerializeSeq<'a>;
+    type SerializeTupleVariant = SerializeSeq<'a>;
+    type SerializeMap = SerializeMap<'a>;
+    type SerializeStruct = SerializeMap<'a>;
+    type SerializeStructVariant = SerializeMap<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(v.into_value_with(self.handle))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(self.handle.str_new(v).as_value())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(self.handle.str_from_slice(v).as_value())
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(self.handle.qnil().as_value())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(self.handle.qnil().as_value())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(self.handle.qnil().as_value())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(self.handle.to_symbol(variant).as_value())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let val = value
+            .serialize(Serializer::new(self.handle))
+            .map_err(|e| e.with_field(variant))?;
+        self.tagged(variant, val)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq<'a>, Error> {
+        Ok(SerializeSeq::new(self.handle, len.unwrap_or(0), None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq<'a>, Error> {
+        Ok(SerializeSeq::new(self.handle, len, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq<'a>, Error> {
+        Ok(SerializeSeq::new(self.handle, len, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq<'a>, Error> {
+        Ok(SerializeSeq::new(self.handle, len, Some(variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeMap<'a>, Error> {
+        Ok(SerializeMap::new(self.handle, None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<SerializeMap<'a>, Error> {
+        Ok(SerializeMap::new(self.handle, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeMap<'a>, Error> {
+        Ok(SerializeMap::new(self.handle, Some(variant)))
+    }
+}
+
+pub struct SerializeSeq<'a> {
+    handle: &'a Ruby,
+    ary: RArray,
+    variant: Option<&'static str>,
+}
+
+impl<'a> SerializeSeq<'a> {
+    fn new(handle: &'a Ruby, len: usize, variant: Option<&'static str>) -> Self {
+        Self {
+            handle,
+            ary: handle.ary_new_capa(len),
+            variant,
+        }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let index = self.ary.len();
+        let val = value
+            .serialize(Serializer::new(self.handle))
+            .map_err(|e| self.with_path(e, index))?;
+        self.ary.push(val)?;
+        Ok(())
+    }
+
+    fn with_path(&self, e: Error, index: usize) -> Error {
+        match self.variant {
+            Some(variant) => e.with_index(index).with_field(variant),
+            None => e.with_index(index),
+        }
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        match self.variant {
+            Some(variant) => Serializer::new(self.handle).tagged(variant, self.ary.as_value()),
+            None => Ok(self.ary.as_value()),
+        }
+    }
+}
+
+impl<'a> ser::SerializeSeq for SerializeSeq<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SerializeSeq<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SerializeSeq<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SerializeSeq<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+pub struct SerializeMap<'a> {
+    handle: &'a Ruby,
+    hash: RHash,
+    key: Option<Value>,
+    variant: Option<&'static str>,
+}
+
+impl<'a> SerializeMap<'a> {
+    fn new(handle: &'a Ruby, variant: Option<&'static str>) -> Self {
+        Self {
+            handle,
+            hash: handle.hash_new(),
+            key: None,
+            variant,
+        }
+    }
+
+    fn with_path(&self, e: Error, field: String) -> Error {
+        match self.variant {
+            Some(variant) => e.with_field(field).with_field(variant),
+            None => e.with_field(field),
+        }
+    }
+
+    fn finish(self) -> Result<Value, Error> {
+        match self.variant {
+            Some(variant) => Serializer::new(self.handle).tagged(variant, self.hash.as_value()),
+            None => Ok(self.hash.as_value()),
+        }
+    }
+}
+
+impl<'a> ser::SerializeMap for SerializeMap<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(Serializer::new(self.handle))?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        let val = value
+            .serialize(Serializer::new(self.handle))
+            .map_err(|e| self.with_path(e, key.to_string()))?;
+        self.hash.aset(key, val)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStruct for SerializeMap<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let val = value
+            .serialize(Serializer::new(self.handle))
+            .map_err(|e| self.with_path(e, key.to_owned()))?;
+        self.hash.aset(self.handle.to_symbol(key), val)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for SerializeMap<'a> {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        self.finish()
+    }
+}
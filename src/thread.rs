@@ -1,21 +1,36 @@
-use std::{fmt, mem::size_of, os::raw::c_void, slice, time::Duration};
+use std::{
+    fmt,
+    mem::size_of,
+    os::raw::c_void,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    ptr, slice,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
 
 use rb_sys::{
-    rb_data_typed_object_wrap, rb_thread_alone, rb_thread_check_ints, rb_thread_create,
-    rb_thread_current, rb_thread_fd_close, rb_thread_fd_writable, rb_thread_interrupted,
-    rb_thread_kill, rb_thread_local_aref, rb_thread_local_aset, rb_thread_main, rb_thread_run,
-    rb_thread_schedule, rb_thread_sleep_deadly, rb_thread_sleep_forever, rb_thread_wait_fd,
-    rb_thread_wait_for, rb_thread_wakeup, rb_thread_wakeup_alive, timeval, VALUE,
+    rb_data_typed_object_wrap, rb_thread_alone, rb_thread_call_with_gvl,
+    rb_thread_call_without_gvl, rb_thread_check_ints, rb_thread_create, rb_thread_current,
+    rb_thread_fd_close, rb_thread_fd_writable, rb_thread_interrupted, rb_thread_kill,
+    rb_thread_local_aref, rb_thread_local_aset, rb_thread_main, rb_thread_run, rb_thread_schedule,
+    rb_thread_sleep_deadly, rb_thread_sleep_forever, rb_thread_wait_fd, rb_thread_wait_for,
+    rb_thread_wakeup, rb_thread_wakeup_alive, timeval, VALUE,
 };
 
 use crate::{
-    api::Ruby,
+    api::{Ruby, RubyGvlState},
     data_type_builder,
-    error::{protect, Error},
+    error::{bug_from_panic, protect, Error, RubyUnavailableError},
+    exception::Exception,
     gc,
     into_value::IntoValue,
     method::{BlockReturn, Thread as _},
     object::Object,
+    r_array::RArray,
     r_file::fd::AsRawFd,
     r_typed_data::RTypedData,
     try_convert::TryConvert,
@@ -166,6 +181,31 @@ impl Ruby {
         unsafe { Thread::from_rb_value_unchecked(rb_thread_main()) }
     }
 
+    /// Return an array of all runnable or stopped threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let t = ruby.thread_create(|ruby| ruby.thread_stop());
+    ///
+    ///     let list = ruby.thread_list()?;
+    ///     assert!(list.includes(ruby.thread_current()));
+    ///     assert!(list.includes(t));
+    ///
+    ///     t.run()?;
+    ///     t.join(None)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn thread_list(&self) -> Result<RArray, Error> {
+        self.class_thread().funcall("list", ())
+    }
+
     /// Attempt to schedule another thread.
     ///
     /// This function blocks until the current thread is re-scheduled.
@@ -175,6 +215,9 @@ impl Ruby {
 
     /// Blocks until the given file descriptor is readable.
     ///
+    /// If called from a non-blocking Fiber with a Fiber scheduler set, this
+    /// waits with the scheduler, allowing other Fibers to run.
+    ///
     /// # Examples
     ///
     /// ```
@@ -210,6 +253,10 @@ impl Ruby {
         T: AsRawFd,
     {
         let fd = fd.as_raw_fd();
+        #[cfg(ruby_gte_3_1)]
+        if let Some(scheduler) = self.fiber_scheduler_current() {
+            return scheduler.io_wait_readable(io_for_fd(self, fd)?);
+        }
         protect(|| {
             unsafe { rb_thread_wait_fd(fd) };
             self.qnil()
@@ -219,6 +266,9 @@ impl Ruby {
 
     /// Blocks until the given file descriptor is writable.
     ///
+    /// If called from a non-blocking Fiber with a Fiber scheduler set, this
+    /// waits with the scheduler, allowing other Fibers to run.
+    ///
     /// # Examples
     ///
     /// ```
@@ -254,6 +304,10 @@ impl Ruby {
         T: AsRawFd,
     {
         let fd = fd.as_raw_fd();
+        #[cfg(ruby_gte_3_1)]
+        if let Some(scheduler) = self.fiber_scheduler_current() {
+            return scheduler.io_wait_writable(io_for_fd(self, fd)?);
+        }
         protect(|| {
             unsafe { rb_thread_fd_writable(fd) };
             self.qnil()
@@ -303,6 +357,9 @@ impl Ruby {
 
     /// Blocks for the given period of time.
     ///
+    /// If called from a non-blocking Fiber with a Fiber scheduler set, this
+    /// sleeps with the scheduler, allowing other Fibers to run.
+    ///
     /// Returns an error if sleep is intrrupted by a signal.
     ///
     /// # Examples
@@ -324,6 +381,10 @@ impl Ruby {
     /// # Ruby::init(example).unwrap()
     /// ```
     pub fn thread_sleep(&self, duration: Duration) -> Result<(), Error> {
+        #[cfg(ruby_gte_3_1)]
+        if let Some(scheduler) = self.fiber_scheduler_current() {
+            return scheduler.kernel_sleep(Some(duration));
+        }
         let t = timeval {
             tv_sec: duration.as_secs() as _,
             tv_usec: duration.subsec_micros() as _,
@@ -337,8 +398,15 @@ impl Ruby {
 
     /// Blocks indefinitely.
     ///
+    /// If called from a non-blocking Fiber with a Fiber scheduler set, this
+    /// sleeps with the scheduler, allowing other Fibers to run.
+    ///
     /// Returns an error if sleep is intrrupted by a signal.
     pub fn thread_sleep_forever(&self) -> Result<(), Error> {
+        #[cfg(ruby_gte_3_1)]
+        if let Some(scheduler) = self.fiber_scheduler_current() {
+            return scheduler.kernel_sleep(None);
+        }
         protect(|| {
             unsafe { rb_thread_sleep_forever() };
             self.qnil()
@@ -393,6 +461,218 @@ impl Ruby {
         })?;
         Ok(())
     }
+
+    /// Create a new [`CancellationToken`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let token = ruby.cancellation_token();
+    ///     assert!(!token.is_cancelled());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn cancellation_token(&self) -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Release the GVL while running `func`, allowing other Ruby threads to
+    /// run.
+    ///
+    /// This should be used around long running Rust code, such as CPU heavy
+    /// computation or blocking IO, that does not need to interact with Ruby.
+    ///
+    /// `func` must be [`Send`] and can not be passed a `Ruby` handle, so can
+    /// not capture or return any Ruby values, and the Ruby API is unavailable
+    /// while it runs ([`Ruby::get`] will return `Err`). See
+    /// [`Ruby::with_gvl`] to briefly reaquire the GVL from within `func`.
+    ///
+    /// `unblock` is called by Ruby, on another thread, if the current thread
+    /// is interrupted (e.g. with `Thread#kill`, `Thread#raise`, or ctrl-c)
+    /// while `func` is running. It should cause `func` to return early, for
+    /// example by setting a flag `func` checks periodically. If `unblock` is
+    /// `None` interrupts will be delayed until `func` completes.
+    ///
+    /// Returns `Err` if the thread was interrupted and the interrupt raised an
+    /// exception once the GVL was reaquired.
+    ///
+    /// # Panics
+    ///
+    /// If `func` panics the panic will be resumed once the GVL has been
+    /// reaquired. If `unblock` panics the process will abort.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let sum = ruby.without_gvl(|| (1..=100_u64).sum::<u64>(), None::<fn()>)?;
+    ///     assert_eq!(sum, 5050);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    ///
+    /// With an `unblock` function:
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cancelled = AtomicBool::new(false);
+    ///     let count = ruby.without_gvl(
+    ///         || {
+    ///             let mut i = 0_u64;
+    ///             while !cancelled.load(Ordering::Relaxed) && i < 1_000_000 {
+    ///                 i += 1;
+    ///             }
+    ///             i
+    ///         },
+    ///         Some(|| cancelled.store(true, Ordering::Relaxed)),
+    ///     )?;
+    ///     assert_eq!(count, 1_000_000);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn without_gvl<F, R, U>(&self, func: F, unblock: Option<U>) -> Result<R, Error>
+    where
+        F: Send + FnOnce() -> R,
+        R: Send,
+        U: Sync + Fn(),
+    {
+        unsafe extern "C" fn call<F, R>(arg: *mut c_void) -> *mut c_void
+        where
+            F: FnOnce() -> R,
+        {
+            let (func, result) = &mut *(arg as *mut (Option<F>, Option<thread::Result<R>>));
+            *result = Some(catch_unwind(AssertUnwindSafe(func.take().unwrap())));
+            ptr::null_mut()
+        }
+
+        unsafe extern "C" fn call_unblock<U>(arg: *mut c_void)
+        where
+            U: Fn(),
+        {
+            if let Err(e) = catch_unwind(AssertUnwindSafe(&*(arg as *const U))) {
+                bug_from_panic(e, "panic in unblock function")
+            }
+        }
+
+        let mut data: (Option<F>, Option<thread::Result<R>>) = (Some(func), None);
+        let (ubf, ubf_arg) = match unblock.as_ref() {
+            Some(u) => (
+                Some(call_unblock::<U> as unsafe extern "C" fn(*mut c_void)),
+                u as *const U as *mut c_void,
+            ),
+            None => (None, ptr::null_mut()),
+        };
+        let res = {
+            let _guard = RubyGvlState::Unlocked.cache();
+            protect(|| {
+                unsafe {
+                    rb_thread_call_without_gvl(
+                        Some(call::<F, R>),
+                        &mut data as *mut _ as *mut c_void,
+                        ubf,
+                        ubf_arg,
+                    )
+                };
+                self.qnil()
+            })
+        };
+        // rb_thread_call_without_gvl checks interrupts both before running
+        // func (in which case func is never run and data.1 is None) and
+        // after the GVL is reaquired (in which case func's result is
+        // discarded). Either raises, so this must return before data.1 is
+        // unwrapped.
+        res?;
+        match data.1.unwrap() {
+            Ok(v) => Ok(v),
+            Err(e) => resume_unwind(e),
+        }
+    }
+
+    /// Reaquire the GVL while running `func`, from code run with
+    /// [`Ruby::without_gvl`].
+    ///
+    /// `func` is passed a `Ruby` handle, and can use the Ruby API. Ruby
+    /// values can not be returned from `func`, as the GVL will be released
+    /// again when it returns. Any [`Error`] should be converted to a Rust
+    /// type, such as a `String`, to be returned.
+    ///
+    /// If the GVL is already held `func` will simply be called.
+    ///
+    /// Returns `Err` if called from a non-Ruby thread.
+    ///
+    /// # Panics
+    ///
+    /// If `func` panics the panic will be resumed once the GVL has been
+    /// released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let len = ruby.without_gvl(
+    ///         || {
+    ///             assert!(Ruby::get().is_err());
+    ///
+    ///             Ruby::with_gvl(|ruby| ruby.str_new("example").len()).unwrap()
+    ///         },
+    ///         None::<fn()>,
+    ///     )?;
+    ///     assert_eq!(len, 7);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn with_gvl<F, R>(func: F) -> Result<R, RubyUnavailableError>
+    where
+        F: FnOnce(&Ruby) -> R,
+        R: Send,
+    {
+        unsafe extern "C" fn call<F, R>(arg: *mut c_void) -> *mut c_void
+        where
+            F: FnOnce(&Ruby) -> R,
+        {
+            let (func, result) = &mut *(arg as *mut (Option<F>, Option<thread::Result<R>>));
+            let func = func.take().unwrap();
+            *result = Some(catch_unwind(AssertUnwindSafe(|| {
+                let _guard = RubyGvlState::Locked.cache();
+                func(&Ruby::get_unchecked())
+            })));
+            ptr::null_mut()
+        }
+
+        match RubyGvlState::current() {
+            RubyGvlState::Locked => Ok(func(unsafe { &Ruby::get_unchecked() })),
+            RubyGvlState::Unlocked => {
+                let mut data: (Option<F>, Option<thread::Result<R>>) = (Some(func), None);
+                unsafe {
+                    rb_thread_call_with_gvl(Some(call::<F, R>), &mut data as *mut _ as *mut c_void)
+                };
+                match data.1.unwrap() {
+                    Ok(v) => Ok(v),
+                    Err(e) => resume_unwind(e),
+                }
+            }
+            RubyGvlState::NonRubyThread => Err(RubyUnavailableError::NonRubyThread),
+        }
+    }
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby's Thread class.
@@ -599,6 +879,235 @@ impl Thread {
     pub fn interrupted(self) -> bool {
         unsafe { rb_thread_interrupted(self.as_rb_value()) != 0 }
     }
+
+    /// Wait for `self` to terminate.
+    ///
+    /// Returns `Ok(true)` once `self` has terminated, or `Ok(false)` if
+    /// `timeout` passes first. If `timeout` is `None` waits indefinitely.
+    ///
+    /// If `self` terminated with an exception, that exception is returned as
+    /// `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let t = ruby.thread_create(|ruby| ruby.thread_stop());
+    ///     assert!(!t.join(Some(Duration::from_millis(10)))?);
+    ///
+    ///     t.run()?;
+    ///     assert!(t.join(None)?);
+    ///
+    ///     let t = ruby.thread_create(|ruby| {
+    ///         Err::<(), _>(Error::new(ruby.exception_arg_error(), "example"))
+    ///     });
+    ///     t.set_report_on_exception(false)?;
+    ///     let err = t.join(None).unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_arg_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn join(self, timeout: Option<Duration>) -> Result<bool, Error> {
+        let res: Value = match timeout {
+            Some(t) => self.funcall("join", (t.as_secs_f64(),))?,
+            None => self.funcall("join", ())?,
+        };
+        Ok(!res.is_nil())
+    }
+
+    /// Wait for `self` to terminate, and return the value it returned.
+    ///
+    /// If `self` terminated with an exception, that exception is returned as
+    /// `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let t = ruby.thread_create_from_fn(|ruby| ruby.str_new("example"));
+    ///     let val: String = t.value()?;
+    ///     assert_eq!(val, "example");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn value<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.funcall("value", ())
+    }
+
+    /// Return the status of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, ThreadStatus};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.thread_current().status()?, ThreadStatus::Run);
+    ///
+    ///     let t = ruby.thread_create(|ruby| ruby.thread_stop());
+    ///     while t.status()? != ThreadStatus::Sleep {
+    ///         ruby.thread_schedule();
+    ///     }
+    ///
+    ///     t.run()?;
+    ///     t.join(None)?;
+    ///     assert_eq!(t.status()?, ThreadStatus::Dead);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn status(self) -> Result<ThreadStatus, Error> {
+        let ruby = Ruby::get_with(self);
+        let val: Value = self.funcall("status", ())?;
+        if val.is_nil() {
+            return Ok(ThreadStatus::Failed);
+        }
+        if val.is_false() {
+            return Ok(ThreadStatus::Dead);
+        }
+        match String::try_convert(val)?.as_str() {
+            "run" => Ok(ThreadStatus::Run),
+            "sleep" => Ok(ThreadStatus::Sleep),
+            "aborting" => Ok(ThreadStatus::Aborting),
+            s => Err(Error::new(
+                ruby.exception_runtime_error(),
+                format!("unknown thread status {:?}", s),
+            )),
+        }
+    }
+
+    /// Return the name of `self`, if set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let t = ruby.thread_create(|ruby| ruby.thread_stop());
+    ///     assert_eq!(t.name()?, None);
+    ///
+    ///     t.set_name(Some("worker"))?;
+    ///     assert_eq!(t.name()?.as_deref(), Some("worker"));
+    ///
+    ///     t.run()?;
+    ///     t.join(None)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn name(self) -> Result<Option<String>, Error> {
+        self.funcall("name", ())
+    }
+
+    /// Set the name of `self`.
+    ///
+    /// `None` removes the name. See [`Thread::name`] for an example.
+    pub fn set_name(self, name: Option<&str>) -> Result<(), Error> {
+        let _: Value = self.funcall("name=", (name,))?;
+        Ok(())
+    }
+
+    /// Raise `ex` in `self`.
+    ///
+    /// If `self` is the current thread this returns `ex` as `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let t = ruby.thread_create(|ruby| ruby.thread_stop());
+    ///     t.set_report_on_exception(false)?;
+    ///     while !t.is_stopped()? {
+    ///         ruby.thread_schedule();
+    ///     }
+    ///
+    ///     let ex = ruby.exception_runtime_error().new_instance(("stop",))?;
+    ///     t.raise(ex)?;
+    ///     let err = t.join(None).unwrap_err();
+    ///     rb_assert!(ruby, r#"err.message == "stop""#, err = err.value().unwrap());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn raise(self, ex: Exception) -> Result<(), Error> {
+        let _: Value = self.funcall("raise", (ex,))?;
+        Ok(())
+    }
+
+    /// Returns whether `self` is dead or sleeping.
+    pub fn is_stopped(self) -> Result<bool, Error> {
+        self.funcall("stop?", ())
+    }
+
+    /// Returns whether `self` is alive.
+    pub fn is_alive(self) -> Result<bool, Error> {
+        self.funcall("alive?", ())
+    }
+
+    /// Returns whether an exception terminating `self` will be reported.
+    ///
+    /// Defaults to the value of `Thread.report_on_exception` at the time
+    /// `self` was created.
+    pub fn report_on_exception(self) -> Result<bool, Error> {
+        self.funcall("report_on_exception", ())
+    }
+
+    /// Set whether an exception terminating `self` will be reported (printed
+    /// to `$stderr`).
+    ///
+    /// This is useful to silence threads whose errors are retrieved with
+    /// [`Thread::join`] or [`Thread::value`].
+    pub fn set_report_on_exception(self, val: bool) -> Result<(), Error> {
+        let _: Value = self.funcall("report_on_exception=", (val,))?;
+        Ok(())
+    }
+
+    /// Returns whether an exception terminating `self` will also be raised
+    /// in the main thread.
+    pub fn abort_on_exception(self) -> Result<bool, Error> {
+        self.funcall("abort_on_exception", ())
+    }
+
+    /// Set whether an exception terminating `self` will also be raised in
+    /// the main thread.
+    pub fn set_abort_on_exception(self, val: bool) -> Result<(), Error> {
+        let _: Value = self.funcall("abort_on_exception=", (val,))?;
+        Ok(())
+    }
+}
+
+/// The status of a [`Thread`], see [`Thread::status`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadStatus {
+    /// The thread is runnable.
+    Run,
+    /// The thread is sleeping, or waiting on IO.
+    Sleep,
+    /// The thread is in the process of terminating.
+    Aborting,
+    /// The thread terminated normally.
+    Dead,
+    /// The thread terminated with an exception.
+    Failed,
 }
 
 impl fmt::Display for Thread {
@@ -639,6 +1148,123 @@ impl TryConvert for Thread {
     }
 }
 
+/// A flag that can be used to cooperatively cancel long running Rust code
+/// when the current Ruby thread is interrupted.
+///
+/// A `CancellationToken` is cheap to clone and can be checked from any
+/// thread with [`is_cancelled`](CancellationToken::is_cancelled), including
+/// from code running with the GVL released. Using
+/// [`unblock_fn`](CancellationToken::unblock_fn) as the `unblock` function
+/// for [`Ruby::without_gvl`] will cancel the token when the thread is
+/// interrupted (e.g. with `Thread#raise`, `Timeout.timeout`, or ctrl-c).
+///
+/// Once back on the Ruby thread [`check_ints`](CancellationToken::check_ints)
+/// converts the interrupt to the pending Ruby exception.
+///
+/// See [`Ruby::cancellation_token`] to create a `CancellationToken`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let token = ruby.cancellation_token();
+///     let count = ruby.without_gvl(
+///         || {
+///             let mut i = 0_u64;
+///             while !token.is_cancelled() && i < 1_000_000 {
+///                 i += 1;
+///             }
+///             i
+///         },
+///         Some(token.unblock_fn()),
+///     )?;
+///     assert_eq!(count, 1_000_000);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Returns whether the token has been cancelled.
+    ///
+    /// This can be called from any thread.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Cancel the token.
+    ///
+    /// This can be called from any thread.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Reset the token to not cancelled.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+
+    /// Returns a function that cancels the token, for use as the `unblock`
+    /// function of [`Ruby::without_gvl`].
+    ///
+    /// See [`CancellationToken`] for an example.
+    pub fn unblock_fn(&self) -> impl Fn() + Send + Sync + 'static {
+        let token = self.clone();
+        move || token.cancel()
+    }
+
+    /// Run any pending interrupts for the current thread if the token has
+    /// been cancelled, or an interrupt is pending.
+    ///
+    /// If an interrupt raises an exception (e.g. `Thread#raise`, or ctrl-c)
+    /// it will be returned as `Err`. Otherwise returns `Ok(())`, and the
+    /// token is left as is, so [`is_cancelled`](CancellationToken::is_cancelled)
+    /// can be used to check if the work should continue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let token = ruby.cancellation_token();
+    ///     let mut sum = 0_u64;
+    ///     for i in 0..1_000_000 {
+    ///         token.check_ints(ruby)?;
+    ///         sum += i;
+    ///     }
+    ///     assert_eq!(sum, 499_999_500_000);
+    ///
+    ///     token.cancel();
+    ///     // no interrupt is pending, so there's no error to return
+    ///     assert!(token.check_ints(ruby).is_ok());
+    ///     assert!(token.is_cancelled());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn check_ints(&self, ruby: &Ruby) -> Result<(), Error> {
+        if self.is_cancelled() || ruby.thread_current().interrupted() {
+            ruby.thread_check_ints()?;
+        }
+        Ok(())
+    }
+}
+
+/// Create a Ruby `IO` for `fd`, that will not close `fd` when garbage
+/// collected.
+#[cfg(ruby_gte_3_1)]
+fn io_for_fd(ruby: &Ruby, fd: crate::r_file::fd::RawFd) -> Result<Value, Error> {
+    ruby.class_io()
+        .funcall("for_fd", (fd, crate::kwargs!(ruby, "autoclose" => false)))
+}
+
 /// Wrap a closure in a Ruby object with no class.
 ///
 /// This effectivly makes the closure's lifetime managed by Ruby. It will be
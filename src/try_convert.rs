@@ -94,6 +94,14 @@ impl TryConvert for isize {
 }
 unsafe impl TryConvertOwned for isize {}
 
+impl TryConvert for i128 {
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Integer::try_convert(val)?.to_i128()
+    }
+}
+unsafe impl TryConvertOwned for i128 {}
+
 impl TryConvert for u8 {
     #[inline]
     fn try_convert(val: Value) -> Result<Self, Error> {
@@ -134,6 +142,14 @@ impl TryConvert for usize {
 }
 unsafe impl TryConvertOwned for usize {}
 
+impl TryConvert for u128 {
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Integer::try_convert(val)?.to_u128()
+    }
+}
+unsafe impl TryConvertOwned for u128 {}
+
 impl TryConvert for f32 {
     #[inline]
     fn try_convert(val: Value) -> Result<Self, Error> {
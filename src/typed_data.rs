@@ -18,8 +18,9 @@ use std::{
 #[cfg(ruby_gte_3_0)]
 use rb_sys::rbimpl_typeddata_flags::{self, RUBY_TYPED_FREE_IMMEDIATELY, RUBY_TYPED_WB_PROTECTED};
 use rb_sys::{
-    self, rb_data_type_struct__bindgen_ty_1, rb_data_type_t, rb_obj_reveal,
-    rb_singleton_class_attached, rb_singleton_class_clone, size_t, VALUE,
+    self, rb_data_type_struct__bindgen_ty_1, rb_data_type_t, rb_gc_writebarrier,
+    rb_gc_writebarrier_unprotect, rb_obj_reveal, rb_singleton_class_attached,
+    rb_singleton_class_clone, size_t, VALUE,
 };
 
 #[cfg(ruby_lt_3_0)]
@@ -33,7 +34,10 @@ use crate::{
     error::{bug_from_panic, Error},
     gc,
     into_value::IntoValue,
+    method::RubyMethod1,
+    module::Module,
     object::Object,
+    r_string::RString,
     r_typed_data::RTypedData,
     scan_args::{get_kwargs, scan_args},
     try_convert::TryConvert,
@@ -804,6 +808,90 @@ where
     pub fn wrap_as(data: T, class: RClass) -> Self {
         get_ruby!().obj_wrap_as(data, class)
     }
+
+    /// Run Ruby's GC write barrier, informing the GC that `self` now holds a
+    /// reference to `value`.
+    ///
+    /// This must be called after storing a Ruby value in a type wrapped with
+    /// the `wb_protected` flag set (see [`DataTypeBuilder::wb_protected`]).
+    /// See [`gc::WbCell`] for a cell type that runs the write barrier
+    /// automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    ///
+    /// use magnus::{
+    ///     function, gc, method, prelude::*, rb_assert, typed_data::Obj, value::Opaque,
+    ///     DataTypeFunctions, Error, Ruby, TypedData, Value,
+    /// };
+    ///
+    /// #[derive(TypedData, Default)]
+    /// #[magnus(class = "Stack", mark, wb_protected)]
+    /// struct Stack {
+    ///     items: RefCell<Vec<Opaque<Value>>>,
+    /// }
+    ///
+    /// impl DataTypeFunctions for Stack {
+    ///     fn mark(&self, marker: &gc::Marker) {
+    ///         for item in self.items.borrow().iter() {
+    ///             marker.mark(*item);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl Stack {
+    ///     fn push(rb_self: Obj<Self>, item: Value) -> Obj<Self> {
+    ///         rb_self.items.borrow_mut().push(item.into());
+    ///         rb_self.write_barrier(item);
+    ///         rb_self
+    ///     }
+    ///
+    ///     fn pop(ruby: &Ruby, rb_self: &Self) -> Option<Value> {
+    ///         rb_self.items.borrow_mut().pop().map(|v| ruby.get_inner(v))
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Stack", ruby.class_object())?;
+    ///     class.define_singleton_method("new", function!(Stack::default, 0))?;
+    ///     class.define_method("push", method!(Stack::push, 1))?;
+    ///     class.define_method("pop", method!(Stack::pop, 0))?;
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///           stack = Stack.new.push("a").push("b")
+    ///           GC.start
+    ///           stack.pop == "b" && stack.pop == "a"
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn write_barrier<U>(self, value: U)
+    where
+        U: ReprValue,
+    {
+        // special constants are never collected, so don't need the write
+        // barrier, and Ruby expects a heap object
+        if !value.is_immediate() {
+            unsafe { rb_gc_writebarrier(self.as_rb_value(), value.as_rb_value()) };
+        }
+    }
+
+    /// Opt `self` out of write barrier protection.
+    ///
+    /// After this is called Ruby will always mark `self` in full during GC,
+    /// the write barrier no longer needs to be run for `self`. This can be
+    /// used as an escape hatch when it is not possible to run the write
+    /// barrier for a value stored in a `wb_protected` type.
+    pub fn write_barrier_unprotect(self) {
+        unsafe { rb_gc_writebarrier_unprotect(self.as_rb_value()) };
+    }
 }
 
 impl<T> Deref for Obj<T>
@@ -1406,3 +1494,112 @@ where
         Ok(clone)
     }
 }
+
+/// Trait for types that can be serialised with Ruby's `Marshal`.
+///
+/// The type is converted to [`Data`](Marshal::Data) with
+/// [`dump`](Marshal::dump), which is then serialised with `Marshal`. On
+/// deserialisation `Data` is converted back with [`load`](Marshal::load).
+///
+/// Call [`define_marshal_methods`](Marshal::define_marshal_methods) to install
+/// the `_dump` and `_load` methods on the type's class, as used by `Marshal`.
+///
+/// `marshal_dump`/`marshal_load` are not used, as `marshal_load` must be
+/// called on an allocated (but uninitialised) object, whereas a wrapped Rust
+/// type is always initialised at allocation.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, prelude::*, rb_assert, typed_data, Error, Ruby};
+///
+/// #[magnus::wrap(class = "Point", free_immediately, size)]
+/// struct Point {
+///     x: isize,
+///     y: isize,
+/// }
+///
+/// impl Point {
+///     fn new(x: isize, y: isize) -> Self {
+///         Self { x, y }
+///     }
+///
+///     fn x(&self) -> isize {
+///         self.x
+///     }
+/// }
+///
+/// impl typed_data::Marshal for Point {
+///     type Data = (isize, isize);
+///
+///     fn dump(&self, _ruby: &Ruby) -> Result<Self::Data, Error> {
+///         Ok((self.x, self.y))
+///     }
+///
+///     fn load(_ruby: &Ruby, (x, y): Self::Data) -> Result<Self, Error> {
+///         Ok(Self { x, y })
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Point", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Point::new, 2))?;
+///     class.define_method("x", magnus::method!(Point::x, 0))?;
+///     <Point as typed_data::Marshal>::define_marshal_methods(ruby)?;
+///
+///     rb_assert!(ruby, "Marshal.load(Marshal.dump(Point.new(1, 2))).x == 1");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait Marshal: TypedData {
+    /// The intermediate Ruby representation of `Self`, serialised with
+    /// `Marshal`.
+    type Data: IntoValue + TryConvert;
+
+    /// Convert `self` to [`Data`](Marshal::Data) for serialisation.
+    fn dump(&self, ruby: &Ruby) -> Result<Self::Data, Error>;
+
+    /// Create `Self` from deserialised [`Data`](Marshal::Data).
+    fn load(ruby: &Ruby, data: Self::Data) -> Result<Self, Error>;
+
+    /// Define `_dump` and `_load` methods on the class returned by
+    /// [`TypedData::class`], so `Self` can be used with Ruby's `Marshal`.
+    ///
+    /// The class must already be defined.
+    fn define_marshal_methods(ruby: &Ruby) -> Result<(), Error> {
+        let class = Self::class(ruby);
+        class.define_method(
+            "_dump",
+            marshal_dump::<Self> as unsafe extern "C" fn(Value, Value) -> Value,
+        )?;
+        class.define_singleton_method(
+            "_load",
+            marshal_load::<Self> as unsafe extern "C" fn(Value, Value) -> Value,
+        )
+    }
+}
+
+unsafe extern "C" fn marshal_dump<T>(rb_self: Value, level: Value) -> Value
+where
+    T: Marshal,
+{
+    let func = |ruby: &Ruby, rb_self: Obj<T>, _level: Value| -> Result<RString, Error> {
+        let data = rb_self.dump(ruby)?;
+        ruby.marshal_dump(data)
+    };
+    RubyMethod1::call_handle_error(func, rb_self, level)
+}
+
+unsafe extern "C" fn marshal_load<T>(class: Value, dumped: Value) -> Value
+where
+    T: Marshal,
+{
+    // `class` may be a subclass of `T::class()`, which should be preserved
+    let func = |ruby: &Ruby, class: RClass, dumped: RString| -> Result<Obj<T>, Error> {
+        let data = ruby.marshal_load(dumped)?;
+        Ok(ruby.obj_wrap_as(T::load(ruby, data)?, class))
+    };
+    RubyMethod1::call_handle_error(func, class, dumped)
+}
@@ -139,6 +139,15 @@ impl IntoValue for isize {
 
 unsafe impl IntoValueFromNative for isize {}
 
+impl IntoValue for i128 {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        handle.integer_from_i128(self).into_value_with(handle)
+    }
+}
+
+unsafe impl IntoValueFromNative for i128 {}
+
 impl IntoValue for u8 {
     #[inline]
     fn into_value_with(self, handle: &Ruby) -> Value {
@@ -184,6 +193,15 @@ impl IntoValue for usize {
 
 unsafe impl IntoValueFromNative for usize {}
 
+impl IntoValue for u128 {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        handle.integer_from_u128(self).into_value_with(handle)
+    }
+}
+
+unsafe impl IntoValueFromNative for u128 {}
+
 impl IntoValue for f32 {
     #[inline]
     fn into_value_with(self, handle: &Ruby) -> Value {
@@ -0,0 +1,40 @@
+#![cfg(unix)]
+
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+
+use magnus::{function, rb_assert};
+
+fn raw_fd(fd: BorrowedFd) -> i32 {
+    fd.as_raw_fd()
+}
+
+#[test]
+fn it_borrows_fds_from_ruby_io() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("raw_fd", function!(raw_fd, 1));
+
+    rb_assert!(ruby, "raw_fd(STDERR) == 2");
+    rb_assert!(
+        ruby,
+        r#"
+        r, _w = IO.pipe
+        r.close
+        begin
+          raw_fd(r)
+        rescue IOError => e
+          e.message == "closed stream"
+        end
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          raw_fd(Object.new)
+        rescue TypeError
+          true
+        end
+        "#
+    );
+}
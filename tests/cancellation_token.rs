@@ -0,0 +1,46 @@
+use std::{hint, thread};
+
+use magnus::{function, rb_assert, Error, Ruby};
+
+fn spin_until_cancelled(ruby: &Ruby) -> Result<bool, Error> {
+    let token = ruby.cancellation_token();
+    ruby.without_gvl(
+        || {
+            while !token.is_cancelled() {
+                hint::spin_loop();
+            }
+            true
+        },
+        Some(token.unblock_fn()),
+    )
+}
+
+#[test]
+fn it_is_cancelled_by_interrupts() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let token = ruby.cancellation_token();
+    assert!(!token.is_cancelled());
+    let other = token.clone();
+    thread::spawn(move || other.cancel()).join().unwrap();
+    assert!(token.is_cancelled());
+    token.reset();
+    assert!(!token.is_cancelled());
+
+    ruby.define_global_function("spin_until_cancelled", function!(spin_until_cancelled, 0));
+    rb_assert!(
+        ruby,
+        r#"
+        t = Thread.new { spin_until_cancelled }
+        t.report_on_exception = false
+        sleep 0.1
+        t.raise("stop")
+        begin
+          t.join
+          false
+        rescue => e
+          e.message == "stop"
+        end
+        "#
+    );
+}
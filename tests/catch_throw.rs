@@ -0,0 +1,38 @@
+use magnus::{function, prelude::*, rb_assert, Error, RArray, RClass, Ruby, Value};
+
+fn index_of(ruby: &Ruby, ary: RArray, target: i64) -> Result<i64, Error> {
+    ruby.catch(ruby.to_symbol("found"), |ruby, tag| {
+        for (i, val) in ary.into_iter().enumerate() {
+            if i64::try_convert(val)? == target {
+                return Err(ruby.throw(tag, i));
+            }
+        }
+        Ok(-1)
+    })
+}
+
+fn nested(ruby: &Ruby) -> Result<Value, Error> {
+    ruby.catch(ruby.to_symbol("inner"), |ruby, _tag| {
+        ruby.eval::<Value>("throw :outer, 5")
+    })
+}
+
+#[test]
+fn it_catches_and_throws() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("index_of", function!(index_of, 2));
+    ruby.define_global_function("nested", function!(nested, 0));
+
+    rb_assert!(ruby, "index_of([5, 6, 7], 6) == 1");
+    rb_assert!(ruby, "index_of([5, 6, 7], 8) == -1");
+
+    // throws for other tags pass through
+    rb_assert!(ruby, "catch(:outer) { nested } == 5");
+
+    let res: Result<Value, Error> = ruby.catch(ruby.to_symbol("a"), |ruby, _tag| {
+        Err(ruby.throw(ruby.to_symbol("b"), 1))
+    });
+    let class: RClass = ruby.eval("UncaughtThrowError").unwrap();
+    assert!(res.unwrap_err().is_kind_of(class));
+}
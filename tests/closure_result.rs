@@ -0,0 +1,29 @@
+use magnus::{function, prelude::*, rb_assert, Error, Exception, Ruby};
+
+#[test]
+fn it_binds_closures_returning_into_errors() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    // the error type can't be inferred from `.into()` alone, so is annotated
+    let positive = |x: i64| -> Result<i64, Error> {
+        if x < 0 {
+            let ruby = Ruby::get().unwrap();
+            let e: Exception = ruby.exception_arg_error().new_instance(("negative",))?;
+            return Err(e.into());
+        }
+        Ok(x)
+    };
+    ruby.define_global_function("positive", function!(positive, 1));
+
+    rb_assert!(ruby, "positive(1) == 1");
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          positive(-1)
+        rescue ArgumentError => e
+          e.message == "negative"
+        end
+        "#
+    );
+}
@@ -0,0 +1,66 @@
+use magnus::{embed::init, rb_assert, Error, IntoValue, Ruby, TryConvert};
+
+#[derive(Debug, PartialEq, TryConvert, IntoValue)]
+struct Config {
+    name: String,
+    #[magnus(default)]
+    verbose: bool,
+    #[magnus(rename = "max-depth", default = "default_max_depth")]
+    max_depth: i64,
+    #[magnus(skip)]
+    cache: Option<String>,
+}
+
+// no field reads from the hash
+#[derive(Debug, PartialEq, TryConvert)]
+struct Skipped {
+    #[magnus(skip)]
+    cache: Option<String>,
+}
+
+fn default_max_depth() -> i64 {
+    16
+}
+
+fn try_config(ruby: &Ruby, src: &str) -> Result<Config, Error> {
+    ruby.eval(src)
+}
+
+#[test]
+fn it_converts_structs_to_and_from_hashes() {
+    let ruby = unsafe { init() };
+
+    let config = try_config(&ruby, r#"{name: "example", "max-depth" => 3}"#).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "example".to_owned(),
+            verbose: false,
+            max_depth: 3,
+            cache: None,
+        }
+    );
+
+    let config = try_config(&ruby, r#"{"name" => "example", verbose: true}"#).unwrap();
+    assert!(config.verbose);
+    assert_eq!(config.max_depth, 16);
+
+    let err = try_config(&ruby, "{verbose: true}").unwrap_err();
+    assert!(err.is_kind_of(ruby.exception_key_error()));
+    assert!(err.to_string().contains(":name"));
+
+    let err = try_config(&ruby, r#"{name: "example", "max-depth" => "3"}"#).unwrap_err();
+    assert!(err.is_kind_of(ruby.exception_type_error()));
+    assert!(err.to_string().contains(":max-depth"));
+
+    let val = config.into_value_with(&ruby);
+    rb_assert!(
+        ruby,
+        r#"val == {name: "example", verbose: true, "max-depth": 16}"#,
+        val
+    );
+
+    let skipped: Skipped = ruby.eval("{name: 'example'}").unwrap();
+    assert_eq!(skipped, Skipped { cache: None });
+    assert!(ruby.eval::<Skipped>("1").is_err());
+}
@@ -0,0 +1,41 @@
+use magnus::{embed::init, rb_assert, Error, IntoValue, Ruby, TryConvert};
+
+#[derive(Debug, PartialEq, TryConvert, IntoValue)]
+#[magnus(rename_all = "snake_case", accept_string)]
+enum Mode {
+    Fast,
+    VerySafe,
+    #[magnus(rename = "paranoid")]
+    ExtraSafe,
+}
+
+#[derive(Debug, PartialEq, TryConvert)]
+enum Strict {
+    On,
+}
+
+fn try_mode(ruby: &Ruby, src: &str) -> Result<Mode, Error> {
+    ruby.eval(src)
+}
+
+#[test]
+fn it_converts_enums_to_and_from_symbols() {
+    let ruby = unsafe { init() };
+
+    assert_eq!(try_mode(&ruby, ":fast").unwrap(), Mode::Fast);
+    assert_eq!(try_mode(&ruby, "\"very_safe\"").unwrap(), Mode::VerySafe);
+    assert_eq!(
+        try_mode(&ruby, "\"paranoid\".to_sym").unwrap(),
+        Mode::ExtraSafe
+    );
+
+    let err = try_mode(&ruby, ":slow").unwrap_err();
+    assert!(err.is_kind_of(ruby.exception_arg_error()));
+    assert!(err.to_string().contains(":fast, :very_safe, :paranoid"));
+
+    assert_eq!(ruby.eval::<Strict>(":On").unwrap(), Strict::On);
+    assert!(ruby.eval::<Strict>("\"On\"").is_err());
+
+    let val = Mode::ExtraSafe.into_value_with(&ruby);
+    rb_assert!(ruby, "val == :paranoid", val);
+}
@@ -0,0 +1,43 @@
+use magnus::{prelude::*, Error, RArray};
+
+#[test]
+fn it_keeps_exception_details_when_mapping_the_message() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let err = ruby
+        .eval::<magnus::Value>(
+            r#"
+            def fail_with_cause
+              raise "inner"
+            rescue
+              raise ArgumentError, "outer"
+            end
+            fail_with_cause
+            "#,
+        )
+        .unwrap_err();
+    let original = err.value().unwrap();
+    let backtrace: RArray = original.funcall("backtrace", ()).unwrap();
+
+    let err = err.map_message(|m| format!("{} for key :name", m));
+    assert!(err.is_kind_of(ruby.exception_arg_error()));
+    let mapped = err.value().unwrap();
+    assert_eq!(
+        mapped.funcall::<_, _, String>("message", ()).unwrap(),
+        "outer for key :name"
+    );
+    let mapped_backtrace: RArray = mapped.funcall("backtrace", ()).unwrap();
+    assert!(mapped_backtrace.eql(backtrace).unwrap());
+    let cause: magnus::Value = mapped.funcall("cause", ()).unwrap();
+    assert_eq!(
+        cause.funcall::<_, _, String>("message", ()).unwrap(),
+        "inner"
+    );
+
+    let err = Error::new(ruby.exception_type_error(), "no implicit conversion");
+    let err = err.map_message(|m| format!("{} for key :name", m));
+    assert!(err.is_kind_of(ruby.exception_type_error()));
+    assert!(err
+        .to_string()
+        .ends_with("no implicit conversion for key :name"));
+}
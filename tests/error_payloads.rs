@@ -0,0 +1,86 @@
+use magnus::{function, method, prelude::*, rb_assert, Error, Ruby};
+
+#[magnus::wrap(class = "ValidationError")]
+struct ValidationError {
+    field: String,
+}
+
+impl ValidationError {
+    fn field(&self) -> String {
+        self.field.clone()
+    }
+}
+
+fn fetch(ruby: &Ruby, key: String) -> Result<(), Error> {
+    Err(ruby.key_error("key not found", ruby.hash_new(), key))
+}
+
+fn finish(ruby: &Ruby) -> Result<(), Error> {
+    Err(ruby.stop_iteration("done"))
+}
+
+fn open(path: String) -> Result<(), std::io::Error> {
+    std::fs::File::open(path).map(|_| ())
+}
+
+fn validate(ruby: &Ruby) -> Result<(), Error> {
+    Err(ruby.typed_data_error(
+        ValidationError {
+            field: String::from("name"),
+        },
+        "can't be blank",
+    ))
+}
+
+#[test]
+fn it_creates_errors_with_payloads() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("fetch", function!(fetch, 1));
+    ruby.define_global_function("finish", function!(finish, 0));
+    ruby.define_global_function("open_file", function!(open, 1));
+    ruby.define_global_function("validate", function!(validate, 0));
+
+    let class = ruby
+        .define_error("ValidationError", ruby.exception_standard_error())
+        .unwrap();
+    class.undef_default_alloc_func();
+    class
+        .define_method("field", method!(ValidationError::field, 0))
+        .unwrap();
+
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          fetch("a")
+        rescue KeyError => e
+          e.key == "a" && e.receiver == {}
+        end
+        "#
+    );
+
+    rb_assert!(ruby, r#"loop { finish } == "done""#);
+
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          open_file("/magnus/does/not/exist")
+        rescue Errno::ENOENT
+          true
+        end
+        "#
+    );
+
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          validate
+        rescue ValidationError => e
+          e.message == "can't be blank" && e.field == "name"
+        end
+        "#
+    );
+}
@@ -0,0 +1,41 @@
+use std::thread;
+
+use magnus::{executor::TaskError, Ruby};
+
+#[test]
+fn it_runs_closures_from_native_threads() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let executor = ruby.executor_new();
+
+    let results = ruby
+        .without_gvl(
+            || {
+                thread::scope(|s| {
+                    let handle = s.spawn(|| {
+                        assert!(Ruby::get().is_err());
+                        executor
+                            .spawn(|ruby| ruby.eval::<String>("'a' * 3").unwrap())
+                            .join()
+                    });
+                    handle.join().unwrap()
+                })
+            },
+            None::<fn()>,
+        )
+        .unwrap();
+    assert_eq!(results.unwrap(), "aaa");
+
+    let task = executor.spawn::<_, ()>(|_ruby| panic!("oops"));
+    let err = ruby
+        .without_gvl(move || task.join(), None::<fn()>)
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(err, TaskError::Panicked(_)));
+
+    executor.shutdown();
+    assert!(matches!(
+        executor.spawn(|_ruby| ()).join(),
+        Err(TaskError::Canceled)
+    ));
+}
@@ -0,0 +1,94 @@
+#![cfg(ruby_gte_3_1)]
+
+use std::time::Duration;
+
+use magnus::{function, rb_assert, Error, RFile, Ruby};
+
+fn sleep_briefly(ruby: &Ruby) -> Result<(), Error> {
+    ruby.thread_sleep(Duration::from_millis(10))
+}
+
+fn sleep_forever(ruby: &Ruby) -> Result<(), Error> {
+    ruby.thread_sleep_forever()
+}
+
+fn wait_readable(ruby: &Ruby, io: RFile) -> Result<(), Error> {
+    ruby.thread_wait_fd(&io)
+}
+
+fn wait_writable(ruby: &Ruby, io: RFile) -> Result<(), Error> {
+    ruby.thread_fd_writable(&io)
+}
+
+#[test]
+fn it_delegates_blocking_operations_to_the_fiber_scheduler() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("sleep_briefly", function!(sleep_briefly, 0));
+    ruby.define_global_function("sleep_forever", function!(sleep_forever, 0));
+    ruby.define_global_function("wait_readable", function!(wait_readable, 1));
+    ruby.define_global_function("wait_writable", function!(wait_writable, 1));
+
+    rb_assert!(
+        ruby,
+        r#"
+        class RecordingScheduler
+          attr_reader :calls
+
+          def initialize
+            @calls = []
+          end
+
+          def block(blocker, timeout = nil)
+            @calls << [:block]
+          end
+
+          def unblock(blocker, fiber)
+            @calls << [:unblock]
+          end
+
+          def io_wait(io, events, timeout)
+            @calls << [:io_wait, events]
+            events
+          end
+
+          def kernel_sleep(duration = nil)
+            @calls << [:kernel_sleep, duration]
+          end
+
+          def close; end
+        end
+
+        scheduler = RecordingScheduler.new
+        r, w = IO.pipe
+        Thread.new do
+          Fiber.set_scheduler(scheduler)
+          Fiber.new(blocking: false) do
+            sleep_briefly
+            sleep_forever
+            wait_readable(r)
+            wait_writable(w)
+          end.resume
+        end.join
+        scheduler.calls == [
+          [:kernel_sleep, 0.01],
+          [:kernel_sleep, nil],
+          [:io_wait, IO::READABLE],
+          [:io_wait, IO::WRITABLE],
+        ]
+        "#
+    );
+
+    // without a non-blocking Fiber the scheduler isn't used
+    rb_assert!(
+        ruby,
+        r#"
+        scheduler = RecordingScheduler.new
+        Thread.new do
+          Fiber.set_scheduler(scheduler)
+          sleep_briefly
+        end.join
+        scheduler.calls.empty?
+        "#
+    );
+}
@@ -0,0 +1,25 @@
+use magnus::{Integer, RString};
+
+#[test]
+fn it_converts_128_bit_integers() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    magnus::rb_assert!(ruby, "val == -(2 ** 127)", val = i128::MIN);
+    magnus::rb_assert!(ruby, "val == 2 ** 128 - 1", val = u128::MAX);
+
+    let i: Integer = ruby.eval("-(2 ** 100)").unwrap();
+    assert_eq!(i.to_i128().unwrap(), -(1i128 << 100));
+    assert!(i.to_u128().is_err());
+
+    let i: Integer = ruby.eval("2 ** 128").unwrap();
+    assert!(i.to_u128().is_err());
+    assert_eq!(i.to_bytes_be(), [&[1][..], &[0; 16][..]].concat());
+
+    let i = ruby.integer_from_bytes_le(&[0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    magnus::rb_assert!(ruby, "i == 2 ** 64", i);
+
+    let s: RString = ruby.eval("(2 ** 70).to_s").unwrap();
+    let big: num_bigint::BigUint = ruby.eval("2 ** 70").unwrap();
+    assert_eq!(big.to_string(), s.to_string().unwrap());
+    magnus::rb_assert!(ruby, "val == 2 ** 70", val = big);
+}
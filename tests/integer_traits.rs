@@ -12,6 +12,11 @@ fn test_all() {
         test_sub(ruby)?;
         test_mul(ruby)?;
         test_div(ruby)?;
+        test_rem(ruby)?;
+        test_div_rem_identity(ruby)?;
+        test_neg(ruby)?;
+        test_shift(ruby)?;
+        test_bitwise(ruby)?;
         test_ord(ruby)?;
         Ok(())
     })
@@ -193,6 +198,117 @@ fn test_div(ruby: &Ruby) -> Result<(), Error> {
     a /= ruby.integer_from_i64(2);
     assert_eq!(a, ruby.integer_from_i64(2));
 
+    // rounds towards negative infinity, like Ruby
+    assert_eq!(
+        ruby.integer_from_i64(-7) / ruby.integer_from_i64(2),
+        ruby.integer_from_i64(-4)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(7) / ruby.integer_from_i64(-2),
+        ruby.integer_from_i64(-4)
+    );
+
+    Ok(())
+}
+
+fn test_div_rem_identity(ruby: &Ruby) -> Result<(), Error> {
+    let values = [
+        ruby.integer_from_i64(7),
+        ruby.integer_from_i64(-7),
+        ruby.integer_from_i64(2),
+        ruby.integer_from_i64(-2),
+        ruby.eval("2**100 + 1")?,
+        ruby.eval("-(2**100 + 1)")?,
+    ];
+    for a in values {
+        for b in values {
+            assert_eq!((a / b) * b + a % b, a);
+        }
+    }
+
+    Ok(())
+}
+
+fn test_rem(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(
+        ruby.integer_from_i64(7) % ruby.integer_from_i64(2),
+        ruby.integer_from_i64(1)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(-7) % ruby.integer_from_i64(2),
+        ruby.integer_from_i64(1)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(7) % ruby.integer_from_i64(-2),
+        ruby.integer_from_i64(-1)
+    );
+
+    let a: Integer = ruby.eval("-(2**1000 + 1)")?;
+    let b = ruby.integer_from_i64(3);
+    let a_b: Integer = ruby.eval("-(2**1000 + 1) % 3")?;
+    assert_eq!(a % b, a_b);
+
+    let mut a = ruby.integer_from_i64(5);
+    a %= ruby.integer_from_i64(3);
+    assert_eq!(a, ruby.integer_from_i64(2));
+
+    Ok(())
+}
+
+fn test_neg(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(-ruby.integer_from_i64(1), ruby.integer_from_i64(-1));
+    assert_eq!(
+        -ruby.integer_from_i64(RUBY_FIXNUM_MIN),
+        ruby.integer_from_u64(RUBY_FIXNUM_MAX + 1)
+    );
+
+    let a: Integer = ruby.eval("2**1000")?;
+    let neg_a: Integer = ruby.eval("-(2**1000)")?;
+    assert_eq!(-a, neg_a);
+
+    Ok(())
+}
+
+fn test_shift(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(ruby.integer_from_i64(1) << 4, ruby.integer_from_i64(16));
+    assert_eq!(ruby.integer_from_i64(-16) >> 2, ruby.integer_from_i64(-4));
+    assert_eq!(ruby.integer_from_i64(-1) >> 100, ruby.integer_from_i64(-1));
+
+    let a: Integer = ruby.eval("2**1000")?;
+    assert_eq!(ruby.integer_from_i64(1) << 1000, a);
+    assert_eq!(a >> 1000, ruby.integer_from_i64(1));
+
+    let mut a = ruby.integer_from_i64(3);
+    a <<= 70;
+    let expected: Integer = ruby.eval("3 << 70")?;
+    assert_eq!(a, expected);
+    a >>= 70;
+    assert_eq!(a, ruby.integer_from_i64(3));
+
+    Ok(())
+}
+
+fn test_bitwise(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) & ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b1000)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) | ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b1110)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) ^ ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b0110)
+    );
+
+    let a: Integer = ruby.eval("2**1000 + 5")?;
+    let b = ruby.integer_from_i64(-4);
+    for (op, result) in [("&", a & b), ("|", a | b), ("^", a ^ b)] {
+        let expected: Integer = ruby.eval(&format!("(2**1000 + 5) {} -4", op))?;
+        assert_eq!(result, expected);
+    }
+
     Ok(())
 }
 
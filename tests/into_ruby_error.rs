@@ -0,0 +1,86 @@
+use std::fmt;
+
+use anyhow::Context;
+use magnus::{function, rb_assert, Error, IntoRubyError, Ruby};
+
+#[derive(Debug, IntoRubyError)]
+#[magnus(class = "Ruby::exception_arg_error")]
+enum ParseError {
+    Missing,
+    #[magnus(class = "Ruby::exception_type_error")]
+    Invalid(std::num::ParseIntError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("missing value"),
+            Self::Invalid(_) => f.write_str("invalid value"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Missing => None,
+            Self::Invalid(e) => Some(e),
+        }
+    }
+}
+
+fn parse_value(s: Option<String>) -> Result<i64, ParseError> {
+    s.ok_or(ParseError::Missing)?
+        .parse()
+        .map_err(ParseError::Invalid)
+}
+
+fn parse_value_anyhow(s: String) -> anyhow::Result<i64> {
+    s.parse::<i64>().context("failed to parse")
+}
+
+#[test]
+fn it_converts_rust_errors() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("parse_value", function!(parse_value, 1));
+    ruby.define_global_function("parse_value_anyhow", function!(parse_value_anyhow, 1));
+
+    rb_assert!(ruby, "parse_value('1') == 1");
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          parse_value(nil)
+        rescue ArgumentError => e
+          e.message == "missing value" && e.cause.nil?
+        end
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          parse_value("x")
+        rescue TypeError => e
+          e.message == "invalid value" &&
+            e.cause.class == RuntimeError &&
+            e.cause.message == "invalid digit found in string"
+        end
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          parse_value_anyhow("x")
+        rescue RuntimeError => e
+          e.message == "failed to parse" &&
+            e.cause.message == "invalid digit found in string"
+        end
+        "#
+    );
+
+    let err = Error::from_std_error(ruby.exception_io_error(), &ParseError::Missing);
+    assert!(err.is_kind_of(ruby.exception_io_error()));
+}
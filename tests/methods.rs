@@ -0,0 +1,56 @@
+use magnus::{embed::init, rb_assert, Error, Ruby};
+
+#[magnus::wrap(class = "Point", free_immediately)]
+struct Point {
+    x: isize,
+    y: isize,
+}
+
+#[magnus::methods]
+impl Point {
+    fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    #[magnus(singleton)]
+    fn origin(_ruby: &Ruby) -> Result<Self, Error> {
+        Ok(Self { x: 0, y: 0 })
+    }
+
+    fn x(&self) -> isize {
+        self.x
+    }
+
+    fn y(&self) -> isize {
+        self.y
+    }
+
+    #[magnus(name = "origin?")]
+    fn is_origin(&self) -> bool {
+        self.x == 0 && self.y == 0
+    }
+
+    #[magnus(visibility = "private")]
+    fn secret(&self) -> isize {
+        self.x * self.y
+    }
+
+    #[magnus(skip)]
+    #[allow(dead_code)]
+    fn skipped(&self) {}
+}
+
+#[test]
+fn it_defines_methods_from_impl() {
+    let ruby = unsafe { init() };
+
+    let class = ruby.define_class("Point", ruby.class_object()).unwrap();
+    Point::define_methods(class).unwrap();
+
+    rb_assert!(ruby, "Point.new(1, 2).x == 1");
+    rb_assert!(ruby, "Point.new(1, 2).y == 2");
+    rb_assert!(ruby, "Point.origin.origin?");
+    rb_assert!(ruby, "!Point.new(1, 2).origin?");
+    rb_assert!(ruby, "Point.private_method_defined?(:secret)");
+    rb_assert!(ruby, "!Point.method_defined?(:skipped)");
+}
@@ -0,0 +1,105 @@
+use std::io::{BufRead, Read, Write};
+
+use magnus::{function, io::RIo, rb_assert, Ruby};
+
+fn count_lines(io: RIo) -> Result<usize, std::io::Error> {
+    let mut count = 0;
+    for line in io.lines() {
+        line?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn read_all(mut io: RIo) -> Result<Vec<u8>, std::io::Error> {
+    let mut buf = Vec::new();
+    io.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_bytewise(mut io: RIo) -> Result<Vec<u8>, std::io::Error> {
+    let mut buf = Vec::new();
+    let mut byte = [0; 1];
+    while io.read(&mut byte)? > 0 {
+        buf.push(byte[0]);
+    }
+    Ok(buf)
+}
+
+fn write_str(mut io: RIo, s: String) -> Result<(), std::io::Error> {
+    io.write_all(s.as_bytes())
+}
+
+#[test]
+fn it_reads_and_writes_ruby_io() {
+    let ruby = unsafe { magnus::embed::init() };
+    ruby.require("stringio").unwrap();
+
+    ruby.define_global_function("count_lines", function!(count_lines, 1));
+    ruby.define_global_function("read_all", function!(read_all, 1));
+    ruby.define_global_function("read_bytewise", function!(read_bytewise, 1));
+    ruby.define_global_function("write_str", function!(write_str, 2));
+
+    rb_assert!(ruby, r#"count_lines(StringIO.new("a\nb\nc")) == 3"#);
+    rb_assert!(ruby, r#"read_all(StringIO.new("\x00\xFF".b)) == [0, 255]"#);
+
+    // the original Ruby exception is passed through
+    rb_assert!(
+        ruby,
+        r#"
+        r, _w = IO.pipe
+        r.close
+        begin
+          read_all(r)
+        rescue IOError => e
+          e.message == "closed stream"
+        end
+        "#
+    );
+
+    // objects that only respond to `read` work too
+    rb_assert!(
+        ruby,
+        r#"
+        reader = Object.new
+        def reader.read(n)
+          return if @done
+          @done = true
+          "x\ny\n"
+        end
+        count_lines(reader) == 2
+        "#
+    );
+
+    // bytes beyond the requested length aren't lost
+    rb_assert!(
+        ruby,
+        r#"
+        reader = Object.new
+        def reader.read(n)
+          return if @done
+          @done = true
+          "abc"
+        end
+        read_bytewise(reader) == "abc".bytes
+        "#
+    );
+
+    // writers reporting more bytes written than were given (e.g. with newline
+    // conversion) don't cause a panic
+    rb_assert!(
+        ruby,
+        r#"
+        writer = Object.new
+        def writer.written
+          @written
+        end
+        def writer.write(s)
+          (@written ||= +"") << s
+          s.bytesize * 2
+        end
+        write_str(writer, "a\nb")
+        writer.written == "a\nb"
+        "#
+    );
+}
@@ -0,0 +1,52 @@
+use std::io::Cursor;
+
+use magnus::{io::RubyIoAdapter, rb_assert};
+
+#[test]
+fn it_exposes_rust_streams_as_io() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let io = ruby.obj_wrap(RubyIoAdapter::reader(Cursor::new(b"abc\ndef".to_vec())));
+    rb_assert!(ruby, r#"io.readpartial(2) == "ab""#, io);
+    rb_assert!(ruby, r#"io.gets == "c\n""#, io);
+    rb_assert!(ruby, r#"io.read.encoding == Encoding::BINARY"#, io);
+    rb_assert!(ruby, "io.eof?", io);
+    rb_assert!(ruby, "io.read(1).nil?", io);
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          io.readpartial(1)
+        rescue EOFError
+          true
+        end
+        "#,
+        io
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        begin
+          io.write("x")
+        rescue IOError => e
+          e.message == "not opened for writing"
+        end
+        "#,
+        io
+    );
+    rb_assert!(ruby, "io.close.nil? && io.closed?", io);
+
+    let io = ruby.obj_wrap(RubyIoAdapter::duplex(Cursor::new(Vec::new())));
+    rb_assert!(ruby, r#"io.write("a", 1) == 2"#, io);
+
+    // converting arguments can call back in to the adapter
+    rb_assert!(
+        ruby,
+        r#"
+        obj = Object.new
+        obj.define_singleton_method(:to_s) { io.flush; "b" }
+        io.write(obj) == 1
+        "#,
+        io
+    );
+}
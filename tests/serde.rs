@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use magnus::{embed::init, rb_assert, Error, Value};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i64, i64),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Drawing {
+    name: String,
+    shapes: Vec<Shape>,
+    #[serde(with = "bytes_format")]
+    thumbnail: Vec<u8>,
+    tags: BTreeMap<String, Option<i64>>,
+}
+
+// serialise a Vec<u8> as bytes, rather than a sequence of integers
+mod bytes_format {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_bytes(v)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        struct V;
+        impl<'de> serde::de::Visitor<'de> for V {
+            type Value = Vec<u8>;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("bytes")
+            }
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(v)
+            }
+        }
+        d.deserialize_byte_buf(V)
+    }
+}
+
+#[test]
+fn it_converts_with_serde() {
+    let ruby = unsafe { init() };
+
+    let mut tags = BTreeMap::new();
+    tags.insert(String::from("a"), Some(1));
+    tags.insert(String::from("b"), None);
+    let drawing = Drawing {
+        name: String::from("example"),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Point(1, 2),
+            Shape::Rect { w: 3, h: 4 },
+        ],
+        thumbnail: vec![0, 255],
+        tags,
+    };
+
+    let val = magnus::serde::to_value(&ruby, &drawing).unwrap();
+    rb_assert!(
+        ruby,
+        r#"val == {
+            name: "example",
+            shapes: [:Empty, {Circle: 1.5}, {Point: [1, 2]}, {Rect: {w: 3, h: 4}}],
+            thumbnail: "\x00\xFF".b,
+            tags: {"a" => 1, "b" => nil},
+        }"#,
+        val
+    );
+    rb_assert!(ruby, "val[:thumbnail].encoding == Encoding::BINARY", val);
+
+    let res: Drawing = magnus::serde::from_value(val).unwrap();
+    assert_eq!(res, drawing);
+
+    let val: Value = ruby
+        .eval(
+            r#"{
+                "name" => "example",
+                "shapes" => [:Empty, {Rect: {w: 3, h: "four"}}],
+                "thumbnail" => "".b,
+                "tags" => {},
+            }"#,
+        )
+        .unwrap();
+    let err = magnus::serde::from_value::<Drawing, _>(val).unwrap_err();
+    assert_eq!(err.path(), "shapes[1].Rect.h");
+
+    let err: Error = err.into();
+    assert!(err.is_kind_of(ruby.exception_type_error()));
+    assert!(err.to_string().ends_with("at shapes[1].Rect.h"));
+}
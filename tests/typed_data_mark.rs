@@ -0,0 +1,62 @@
+use std::cell::Cell;
+
+use magnus::{embed::init, value::Opaque, DataTypeFunctions, RString, TypedData};
+
+#[derive(DataTypeFunctions, TypedData)]
+#[magnus(class = "Pair", free_immediately, mark_fields)]
+struct Pair {
+    left: Opaque<RString>,
+    right: Opaque<RString>,
+    #[magnus(skip_mark)]
+    label: String,
+}
+
+#[derive(DataTypeFunctions, TypedData)]
+#[magnus(class = "Boxed", mark_fields, compact)]
+struct Boxed {
+    inner: Cell<Opaque<RString>>,
+}
+
+// `mark` alone doesn't generate field marking, so fields needn't be `Mark`
+#[derive(DataTypeFunctions, TypedData)]
+#[magnus(class = "Plain", mark)]
+struct Plain {
+    name: String,
+    count: u64,
+}
+
+#[test]
+fn it_marks_fields() {
+    let ruby = unsafe { init() };
+
+    ruby.define_class("Pair", ruby.class_object()).unwrap();
+    ruby.define_class("Boxed", ruby.class_object()).unwrap();
+    ruby.define_class("Plain", ruby.class_object()).unwrap();
+
+    let pair = ruby.obj_wrap(Pair {
+        left: ruby.str_new("left").into(),
+        right: ruby.str_new("right").into(),
+        label: "pair".to_owned(),
+    });
+    let boxed = ruby.obj_wrap(Boxed {
+        inner: Cell::new(ruby.str_new("inner").into()),
+    });
+
+    let plain = ruby.obj_wrap(Plain {
+        name: "plain".to_owned(),
+        count: 1,
+    });
+
+    ruby.gc_start();
+    let _: bool = ruby.eval("GC.compact rescue nil; true").unwrap();
+
+    assert_eq!(ruby.get_inner(pair.left).to_string().unwrap(), "left");
+    assert_eq!(ruby.get_inner(pair.right).to_string().unwrap(), "right");
+    assert_eq!(pair.label, "pair");
+    assert_eq!(
+        ruby.get_inner(boxed.inner.get()).to_string().unwrap(),
+        "inner"
+    );
+    assert_eq!(plain.name, "plain");
+    assert_eq!(plain.count, 1);
+}
@@ -0,0 +1,67 @@
+use magnus::{
+    method, rb_assert,
+    typed_data::{self, Obj},
+    Error, RClass, Ruby,
+};
+
+#[magnus::wrap(class = "Point", free_immediately, size)]
+struct Point {
+    x: isize,
+    y: isize,
+}
+
+impl Point {
+    // wraps as the receiver, so subclasses create instances of themselves
+    fn new(ruby: &Ruby, class: RClass, x: isize, y: isize) -> Obj<Self> {
+        ruby.obj_wrap_as(Self { x, y }, class)
+    }
+
+    fn x(&self) -> isize {
+        self.x
+    }
+
+    fn y(&self) -> isize {
+        self.y
+    }
+}
+
+impl typed_data::Marshal for Point {
+    type Data = (isize, isize);
+
+    fn dump(&self, _ruby: &Ruby) -> Result<Self::Data, Error> {
+        Ok((self.x, self.y))
+    }
+
+    fn load(_ruby: &Ruby, (x, y): Self::Data) -> Result<Self, Error> {
+        Ok(Self { x, y })
+    }
+}
+
+#[test]
+fn it_round_trips_subclasses_through_marshal() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Point", ruby.class_object()).unwrap();
+    class
+        .define_singleton_method("new", method!(Point::new, 2))
+        .unwrap();
+    class.define_method("x", method!(Point::x, 0)).unwrap();
+    class.define_method("y", method!(Point::y, 0)).unwrap();
+    <Point as typed_data::Marshal>::define_marshal_methods(&ruby).unwrap();
+
+    rb_assert!(
+        ruby,
+        r#"
+        point = Marshal.load(Marshal.dump(Point.new(1, 2)))
+        point.class == Point && point.x == 1 && point.y == 2
+        "#
+    );
+    rb_assert!(
+        ruby,
+        r#"
+        class Point3 < Point; end
+        point = Marshal.load(Marshal.dump(Point3.new(3, 4)))
+        point.class == Point3 && point.x == 3 && point.y == 4
+        "#
+    );
+}
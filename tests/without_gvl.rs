@@ -0,0 +1,32 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use magnus::Ruby;
+
+static DONE: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn it_allows_other_threads_to_run() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.thread_create(|_ruby| DONE.store(true, Ordering::SeqCst));
+
+    let done = ruby
+        .without_gvl(
+            || {
+                assert!(Ruby::get().is_err());
+                let start = Instant::now();
+                while !DONE.load(Ordering::SeqCst) && start.elapsed() < Duration::from_secs(5) {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                DONE.load(Ordering::SeqCst)
+            },
+            None::<fn()>,
+        )
+        .unwrap();
+
+    assert!(done);
+    assert!(Ruby::get().is_ok());
+}